@@ -0,0 +1,346 @@
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use quote::{quote, quote_spanned};
+use syn::{
+    ext::IdentExt,
+    parse::{Parse, ParseStream, Parser},
+    punctuated::Punctuated,
+    token::Brace,
+    Attribute, Error, Expr, ExprLit, Ident, Lit, LitStr, Meta, Path, Token,
+};
+
+/// Keys consumed by `SchemaAttributes` that are never emitted as schema keywords.
+const RESERVED: &[&str] = &[
+    "skip",
+    "flatten",
+    "integer",
+    "optional",
+    "crate",
+    "key_min_length",
+    "key_max_length",
+    "propertyNames_pattern",
+    "hoist_required",
+    "allow_unknown_fields",
+    "deny_unknown_fields",
+    "source_comment",
+    "schema",
+    "with_schema",
+    "with",
+    "schema_fn",
+    "rename_prefix",
+    "repr",
+    "as",
+    "bytes",
+    "variant_examples",
+    "inline",
+    "ref",
+    "id",
+    "draft",
+];
+
+/// Rust keywords accepted as keys.
+const KEYWORDS: &[&str] = &["const", "ref", "as"];
+
+pub(crate) fn is_reserved(key: &str) -> bool {
+    RESERVED.contains(&key)
+}
+
+/// Options of `#[json_schema(...)]` that drive code generation rather than being
+/// emitted as schema keywords.
+#[derive(Default)]
+pub(crate) struct SchemaAttributes {
+    pub(crate) skip: bool,
+    /// Merge the properties of the field into the parent object.
+    pub(crate) flatten: bool,
+    pub(crate) enum_values: bool,
+    /// Emit `"type": "integer"` for a numeric field regardless of the config.
+    pub(crate) integer: bool,
+    /// Move required properties shared by every `oneOf` branch up to the enum schema.
+    pub(crate) hoist_required: bool,
+    /// Let the variants of an internally tagged enum accept keys other than their fields.
+    pub(crate) allow_unknown_fields: bool,
+    /// Reject keys of a struct other than its fields, like `#[serde(deny_unknown_fields)]`.
+    pub(crate) deny_unknown_fields: bool,
+    /// Add an `examples` array holding one minimal instance of every enum variant.
+    pub(crate) variant_examples: bool,
+    /// Add a `$comment` naming the Rust type the schema was derived from.
+    pub(crate) source_comment: bool,
+    /// JSON schema used in place of the schema of the field type, from `schema` or
+    /// `with_schema`.
+    pub(crate) schema: Option<LitStr>,
+    /// Draft the container targets, `"07"`, `"2019-09"` or `"2020-12"`.
+    pub(crate) draft: Option<LitStr>,
+    /// Prefix prepended to the name of every property.
+    pub(crate) rename_prefix: Option<LitStr>,
+    /// Representation of a unit enum, `"int"` for its discriminants.
+    pub(crate) repr: Option<LitStr>,
+    /// Representation of a unit enum used as a set of flags, `"array_of_variants"`.
+    pub(crate) representation: Option<LitStr>,
+    /// Encoding of a byte array serialized as a string, `"base64"`.
+    pub(crate) bytes: Option<LitStr>,
+    /// Whether the field must be present (`required`) or not (`optional`) regardless of
+    /// its type.
+    pub(crate) required: Option<bool>,
+    /// Inline (`inline`) or reference (`ref`) the field type regardless of the config.
+    pub(crate) inline: Option<bool>,
+    /// `$id` of the root schema.
+    pub(crate) id: Option<LitStr>,
+    /// Function returning the schema used in place of the schema of the field type.
+    pub(crate) with: Option<Path>,
+    /// Function returning the schema of the whole container.
+    pub(crate) schema_fn: Option<Path>,
+    /// Path the generated code uses in place of `serde_json`.
+    pub(crate) crate_path: Option<Path>,
+    /// Keywords of the `propertyNames` schema constraining map keys.
+    pub(crate) property_names: Vec<(&'static str, Expr)>,
+    /// Every key given, used to check keywords against the annotated type.
+    pub(crate) keys: Vec<Ident>,
+}
+
+impl SchemaAttributes {
+    pub(crate) fn try_from_attributes(attrs: &[Attribute]) -> Result<Self, Error> {
+        let mut this = Self::default();
+        for attr in attrs {
+            if !attr.path().is_ident("json_schema") {
+                continue;
+            }
+            // Literal keys are only emitted as keywords, but are parsed here to report errors.
+            parse_literal_keys(attr)?;
+            for meta in parse_metas(attr)? {
+                if meta.path().is_ident("skip") {
+                    this.skip = true;
+                }
+                if meta.path().is_ident("flatten") {
+                    this.flatten = true;
+                }
+                if meta.path().is_ident("integer") {
+                    this.integer = true;
+                }
+                if meta.path().is_ident("enum_values") {
+                    this.enum_values = true;
+                }
+                if meta.path().is_ident("hoist_required") {
+                    this.hoist_required = true;
+                }
+                if meta.path().is_ident("deny_unknown_fields") {
+                    this.deny_unknown_fields = true;
+                }
+                if meta.path().is_ident("allow_unknown_fields") {
+                    this.allow_unknown_fields = true;
+                }
+                if meta.path().is_ident("variant_examples") {
+                    this.variant_examples = true;
+                }
+                if meta.path().is_ident("inline") || meta.path().is_ident("r#ref") {
+                    let inline = meta.path().is_ident("inline");
+                    if this.inline == Some(!inline) {
+                        return Err(Error::new_spanned(
+                            meta.path(),
+                            "`inline` and `ref` cannot be used together",
+                        ));
+                    }
+                    this.inline = Some(inline);
+                }
+                // `required = [...]` is the keyword, only the bare flag is an option.
+                let required_flag = matches!(&meta, Meta::Path(path) if path.is_ident("required"));
+                if meta.path().is_ident("optional") || required_flag {
+                    let required = required_flag;
+                    if this.required == Some(!required) {
+                        return Err(Error::new_spanned(
+                            meta.path(),
+                            "`optional` and `required` cannot be used together",
+                        ));
+                    }
+                    this.required = Some(required);
+                }
+                if meta.path().is_ident("source_comment") {
+                    this.source_comment = true;
+                }
+                if meta.path().is_ident("crate") {
+                    let name_value = meta.require_name_value()?;
+                    this.crate_path = Some(parse_lit_str(&name_value.value)?);
+                }
+                if meta.path().is_ident("schema") || meta.path().is_ident("with_schema") {
+                    let name_value = meta.require_name_value()?;
+                    this.schema = Some(require_lit_str(&name_value.value)?);
+                }
+                if meta.path().is_ident("with") {
+                    let name_value = meta.require_name_value()?;
+                    this.with = Some(parse_lit_str(&name_value.value)?);
+                }
+                if meta.path().is_ident("schema_fn") {
+                    let name_value = meta.require_name_value()?;
+                    this.schema_fn = Some(parse_lit_str(&name_value.value)?);
+                }
+                if meta.path().is_ident("rename_prefix") {
+                    let name_value = meta.require_name_value()?;
+                    this.rename_prefix = Some(require_lit_str(&name_value.value)?);
+                }
+                if meta.path().is_ident("repr") {
+                    let name_value = meta.require_name_value()?;
+                    this.repr = Some(require_lit_str(&name_value.value)?);
+                }
+                if meta.path().is_ident("r#as") {
+                    let name_value = meta.require_name_value()?;
+                    this.representation = Some(require_lit_str(&name_value.value)?);
+                }
+                if meta.path().is_ident("bytes") {
+                    let name_value = meta.require_name_value()?;
+                    this.bytes = Some(require_lit_str(&name_value.value)?);
+                }
+                if meta.path().is_ident("id") {
+                    let name_value = meta.require_name_value()?;
+                    this.id = Some(require_lit_str(&name_value.value)?);
+                }
+                if meta.path().is_ident("draft") {
+                    let name_value = meta.require_name_value()?;
+                    this.draft = Some(require_lit_str(&name_value.value)?);
+                }
+                if meta.path().is_ident("key_min_length") {
+                    let name_value = meta.require_name_value()?;
+                    this.property_names
+                        .push(("minLength", name_value.value.clone()));
+                }
+                if meta.path().is_ident("key_max_length") {
+                    let name_value = meta.require_name_value()?;
+                    this.property_names
+                        .push(("maxLength", name_value.value.clone()));
+                }
+                if meta.path().is_ident("propertyNames_pattern") {
+                    let name_value = meta.require_name_value()?;
+                    this.property_names
+                        .push(("pattern", name_value.value.clone()));
+                }
+                if let Some(ident) = meta.path().get_ident() {
+                    // Keys from every `#[json_schema(...)]` attribute are merged, so a
+                    // repeated key would silently override the earlier one.
+                    let ident = ident.unraw();
+                    if this.keys.contains(&ident) {
+                        return Err(Error::new_spanned(
+                            meta.path(),
+                            format!("duplicate `{ident}` attribute"),
+                        ));
+                    }
+                    this.keys.push(ident);
+                }
+            }
+        }
+        Ok(this)
+    }
+
+    pub(crate) fn rename_prefix(&self) -> String {
+        self.rename_prefix
+            .as_ref()
+            .map(LitStr::value)
+            .unwrap_or_default()
+    }
+}
+
+/// Parses the arguments of a `#[json_schema(...)]` attribute, except those with a string
+/// literal key, which are parsed by `parse_literal_keys`.
+///
+/// `Meta` rejects keywords as keys, so keys such as `const` are passed to it as raw
+/// identifiers. Use `Ident::unraw` to get the key name back. Values written as a JSON
+/// object in braces, e.g. `patternProperties = { "^S_": { "type": "string" } }`, are
+/// passed to it as a `serde_json::json!` invocation.
+pub(crate) fn parse_metas(attr: &Attribute) -> Result<Punctuated<Meta, Token![,]>, Error> {
+    let mut tokens = TokenStream::new();
+    for entry in entries(attr)? {
+        if has_literal_key(&entry) {
+            continue;
+        }
+        let mut previous: Option<TokenTree> = None;
+        for token in entry {
+            let at_key = previous.is_none();
+            let at_value =
+                matches!(&previous, Some(TokenTree::Punct(punct)) if punct.as_char() == '=');
+            match &token {
+                TokenTree::Ident(ident)
+                    if at_key && KEYWORDS.iter().any(|keyword| ident == keyword) =>
+                {
+                    tokens.extend([TokenTree::Ident(Ident::new_raw(
+                        &ident.to_string(),
+                        ident.span(),
+                    ))]);
+                }
+                TokenTree::Group(group) if at_value && group.delimiter() == Delimiter::Brace => {
+                    tokens.extend(quote_spanned! {group.span()=> serde_json::json!(#group) });
+                }
+                token => tokens.extend([token.clone()]),
+            }
+            previous = Some(token);
+        }
+        tokens.extend(quote! { , });
+    }
+    Punctuated::<Meta, Token![,]>::parse_terminated.parse2(tokens)
+}
+
+/// Parses the arguments of a `#[json_schema(...)]` attribute whose key is a string
+/// literal, such as the vendor extension in `"x-internal" = true`.
+pub(crate) fn parse_literal_keys(attr: &Attribute) -> Result<Vec<(LitStr, Expr)>, Error> {
+    let mut pairs = Vec::new();
+    for entry in entries(attr)? {
+        if !has_literal_key(&entry) {
+            continue;
+        }
+        let pair = |input: ParseStream| {
+            let key: LitStr = input.parse()?;
+            input.parse::<Token![=]>()?;
+            let value = match input.peek(Brace) {
+                true => {
+                    let group: TokenTree = input.parse()?;
+                    syn::parse2(quote_spanned! {group.span()=> serde_json::json!(#group) })?
+                }
+                false => input.parse()?,
+            };
+            Ok((key, value))
+        };
+        pairs.push(pair.parse2(entry)?);
+    }
+    Ok(pairs)
+}
+
+/// Splits the arguments of an attribute at the commas separating them.
+fn entries(attr: &Attribute) -> Result<Vec<TokenStream>, Error> {
+    let meta_list = attr.meta.require_list()?;
+    let mut entries = Vec::new();
+    let mut entry = TokenStream::new();
+    for token in meta_list.tokens.clone() {
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => {
+                entries.push(std::mem::take(&mut entry));
+            }
+            _ => entry.extend([token]),
+        }
+    }
+    if !entry.is_empty() {
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+fn has_literal_key(entry: &TokenStream) -> bool {
+    matches!(
+        entry.clone().into_iter().next(),
+        Some(TokenTree::Literal(_))
+    )
+}
+
+/// Returns a string literal value as is, e.g. the JSON in `schema = "{}"`.
+fn require_lit_str(value: &Expr) -> Result<LitStr, Error> {
+    match value {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit), ..
+        }) => Ok(lit.clone()),
+        _ => Err(Error::new_spanned(value, "expected a string literal")),
+    }
+}
+
+/// Parses the contents of a string literal value, e.g. the path in `crate = "my_json"`.
+fn parse_lit_str<T: Parse>(value: &Expr) -> Result<T, Error> {
+    match value {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit), ..
+        }) => lit.parse(),
+        _ => Err(Error::new_spanned(value, "expected a string literal")),
+    }
+}
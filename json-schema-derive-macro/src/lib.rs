@@ -1,10 +1,14 @@
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{quote, quote_spanned, ToTokens};
 use syn::{
-    parse_macro_input, punctuated::Punctuated, Attribute, Data, DataEnum, DeriveInput, Error,
-    Field, Fields, FieldsNamed, FieldsUnnamed, MetaNameValue, Token, Type, Variant,
+    ext::IdentExt, parse_macro_input, parse_quote, punctuated::Punctuated, spanned::Spanned,
+    Attribute, Data, DataEnum, DeriveInput, Error, Expr, ExprLit, Field, Fields, FieldsNamed,
+    FieldsUnnamed, GenericArgument, Ident, Lit, Meta, PathArguments, Token, Type, Variant,
 };
 
+use attributes::SchemaAttributes;
+
+mod attributes;
 #[cfg(feature = "serde-compat")]
 mod serde_compat;
 
@@ -12,31 +16,203 @@ mod serde_compat;
 pub fn json_schema_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
+    let def_name = name.to_string();
+
+    let container = match SchemaAttributes::try_from_attributes(&input.attrs) {
+        Ok(container) => container,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    if let Err(err) = check_attributes(&input) {
+        return err.to_compile_error().into();
+    }
 
     let body = match &input.data {
+        // The container schema comes from a user function, so the fields are not inspected.
+        _ if container.schema_fn.is_some() => {
+            let schema_fn = &container.schema_fn;
+            quote! { #schema_fn() }
+        }
         Data::Struct(data) => match &data.fields {
-            Fields::Named(fields) => struct_named(fields, &input.attrs),
+            Fields::Named(fields) => struct_named(fields, &input.attrs, name),
             Fields::Unnamed(fields) => struct_unnamed(fields, &input.attrs),
             Fields::Unit => struct_field_unit(&input.attrs),
         },
-        Data::Enum(data) => data_enum(data, &input.attrs),
-        _ => {
-            return Error::new_spanned(&input.ident, "Only structs and enums are supported")
+        Data::Enum(data) if data.variants.is_empty() => {
+            return Error::new_spanned(&input.ident, "enums must have at least one variant")
                 .to_compile_error()
                 .into()
         }
+        Data::Enum(data) => data_enum(data, &input.attrs, name),
+        Data::Union(data) => {
+            return Error::new_spanned(data.union_token, "Only structs and enums are supported")
+                .to_compile_error()
+                .into()
+        }
+    };
+    // Every type parameter must implement `JsonSchema` for the fields using it to.
+    let mut generics = input.generics.clone();
+    let type_params: Vec<_> = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect();
+    for param in type_params {
+        generics
+            .make_where_clause()
+            .predicates
+            .push(parse_quote! { #param: JsonSchema });
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    // Every instantiation of a generic type gets its own `$defs` entry.
+    let type_args = input.generics.type_params().map(|param| {
+        let ident = &param.ident;
+        quote! { <#ident as JsonSchema>::json_schema_name() }
+    });
+    let const_args = input.generics.const_params().map(|param| {
+        let ident = &param.ident;
+        quote! {
+            json_schema_derive::__private::Cow::Owned(json_schema_derive::__private::format!("{}", #ident))
+        }
+    });
+    let args: Vec<_> = type_args.chain(const_args).collect();
+    let schema_name = match args.is_empty() {
+        true => quote! { json_schema_derive::__private::Cow::Borrowed(#def_name) },
+        false => quote! { json_schema_derive::__private::generic_name(#def_name, &[#(#args),*]) },
+    };
+    // A static in a generic function is shared by all its instantiations, so only
+    // types without type or const parameters get a validator cache.
+    let validator_cell = (input.generics.type_params().next().is_none()
+        && input.generics.const_params().next().is_none())
+    .then(|| {
+        quote! {
+            fn validator_cell() -> Option<&'static json_schema_derive::ValidatorCell> {
+                static CELL: json_schema_derive::ValidatorCell = json_schema_derive::ValidatorCell::new();
+                Some(&CELL)
+            }
+        }
+    });
+    if let Some(schema) = &container.schema {
+        return Error::new_spanned(
+            schema,
+            "`schema` replaces the schema of a field; choose the draft of a type with `draft = \"...\"`",
+        )
+        .to_compile_error()
+        .into();
+    }
+    let draft = match container.draft.as_ref().map(|draft| (draft, draft.value())) {
+        None => None,
+        Some((draft, value)) => {
+            let variant = match value.as_str() {
+                "07" => quote! { Draft07 },
+                "2019-09" => quote! { Draft201909 },
+                "2020-12" => quote! { Draft202012 },
+                _ => {
+                    return Error::new_spanned(
+                        draft,
+                        "expected `draft = \"07\"`, `\"2019-09\"` or `\"2020-12\"`",
+                    )
+                    .to_compile_error()
+                    .into()
+                }
+            };
+            Some(quote! {
+                const DRAFT: json_schema_derive::Draft = json_schema_derive::Draft::#variant;
+            })
+        }
     };
+    let schema_id = container.id.as_ref().map(|id| {
+        quote! {
+            fn json_schema_id() -> Option<&'static str> {
+                Some(#id)
+            }
+        }
+    });
+
+    let body = match container.source_comment {
+        true => {
+            let comment = format!("Generated from Rust type {name}");
+            quote! {{
+                let mut schema = #body;
+                if let serde_json::Value::Object(map) = &mut schema {
+                    map.insert("$comment".into(), serde_json::Value::String(#comment.into()));
+                }
+                schema
+            }}
+        }
+        false => body,
+    };
+
+    let implementation = quote! {
+        impl #impl_generics JsonSchema for #name #ty_generics #where_clause {
+            #draft
 
-    quote! {
-        impl JsonSchema for #name {
             fn json_schema() -> serde_json::Value {
-                #body
+                Self::json_schema_with(&json_schema_derive::JsonSchemaConfig {
+                    draft: Self::DRAFT,
+                    ..Default::default()
+                })
+            }
+
+            fn json_schema_name() -> json_schema_derive::__private::Cow<'static, str> {
+                #schema_name
+            }
+
+            #schema_id
+
+            #validator_cell
+
+            fn json_schema_in(
+                config: &json_schema_derive::JsonSchemaConfig,
+                defs: &mut json_schema_derive::Definitions,
+            ) -> serde_json::Value {
+                config.define(defs, &Self::json_schema_name(), |defs| #body)
             }
         }
+    };
+
+    // Generated code refers to `serde_json` by name, so a custom path is aliased to it.
+    match &container.crate_path {
+        Some(path) => quote! {
+            const _: () = {
+                use #path as serde_json;
+                #implementation
+            };
+        },
+        None => implementation,
     }
     .into()
 }
 
+/// Parses the attributes of every field and variant, so that invalid or duplicate
+/// keys are reported before the helpers below read the attributes again.
+fn check_attributes(input: &DeriveInput) -> Result<(), Error> {
+    let fields: Vec<&Field> = match &input.data {
+        Data::Struct(data) => data.fields.iter().collect(),
+        Data::Enum(data) => data.variants.iter().flat_map(|v| &v.fields).collect(),
+        Data::Union(_) => Vec::new(),
+    };
+    let variants: Vec<&Variant> = match &input.data {
+        Data::Enum(data) => data.variants.iter().collect(),
+        _ => Vec::new(),
+    };
+    let attrs = std::iter::once(&input.attrs)
+        .chain(variants.iter().map(|v| &v.attrs))
+        .chain(fields.iter().map(|f| &f.attrs));
+
+    let mut errors: Option<Error> = None;
+    for attrs in attrs {
+        let result = SchemaAttributes::try_from_attributes(attrs).map(|_| ());
+        #[cfg(feature = "serde-compat")]
+        let result = result.and(serde_compat::check_attributes(attrs));
+        if let Err(err) = result {
+            match &mut errors {
+                Some(errors) => errors.combine(err),
+                None => errors = Some(err),
+            }
+        }
+    }
+    errors.map_or(Ok(()), Err)
+}
+
 fn struct_field_unit(attrs: &[Attribute]) -> proc_macro2::TokenStream {
     let attributes = parse_attributes(attrs);
     quote! {{
@@ -47,20 +223,82 @@ fn struct_field_unit(attrs: &[Attribute]) -> proc_macro2::TokenStream {
     }}
 }
 
-fn struct_named(fields: &FieldsNamed, attrs: &[Attribute]) -> proc_macro2::TokenStream {
+fn struct_named(
+    fields: &FieldsNamed,
+    attrs: &[Attribute],
+    ident: &Ident,
+) -> proc_macro2::TokenStream {
     let attributes = parse_attributes(attrs);
-    let generate_field_properties = field_props(fields);
+    let generate_field_properties = field_props(fields, &FieldNaming::from_attributes(attrs));
+    let merge_all_of = merge_all_of();
+    let title = ident.to_string();
+    // Inserted ahead of `properties` so that it keeps its place when re-inserted along
+    // with the other attributes.
+    let description = doc_description(attrs).map(|description| {
+        quote! { map.insert("description".into(), #description); }
+    });
+    let schema_attrs = SchemaAttributes::try_from_attributes(attrs).unwrap_or_default();
+    #[cfg(feature = "serde-compat")]
+    let deny_unknown_fields =
+        schema_attrs.deny_unknown_fields || serde_compat::deny_unknown_fields(attrs);
+    #[cfg(not(feature = "serde-compat"))]
+    let deny_unknown_fields = schema_attrs.deny_unknown_fields;
+    // With flattened fields, `unevaluatedProperties` accepts every key evaluated by any
+    // subschema rather than only the properties listed next to it. Draft 7 lacks it.
+    let deny_unknown_fields = match deny_unknown_fields {
+        true if fields.named.iter().any(is_flattened) => quote! {
+            else {
+                let keyword = match config.draft {
+                    json_schema_derive::Draft::Draft07 => "additionalProperties",
+                    _ => "unevaluatedProperties",
+                };
+                // The variants of flattened enums still check their keys, which are
+                // listed as properties so that `additionalProperties` accepts them.
+                if config.draft == json_schema_derive::Draft::Draft07 {
+                    let keys: json_schema_derive::__private::Vec<json_schema_derive::__private::String> = all_of
+                        .iter()
+                        .filter_map(|schema| schema.get("oneOf").and_then(serde_json::Value::as_array))
+                        .flatten()
+                        .filter_map(|variant| variant.get("properties").and_then(serde_json::Value::as_object))
+                        .flat_map(|properties| properties.keys().cloned())
+                        .collect();
+                    if let Some(serde_json::Value::Object(properties)) = map.get_mut("properties") {
+                        for key in keys {
+                            properties.entry(key).or_insert(serde_json::Value::Bool(true));
+                        }
+                    }
+                }
+                map.insert(keyword.into(), serde_json::Value::Bool(false));
+            }
+        },
+        true => quote! {
+            else {
+                map.insert("additionalProperties".into(), serde_json::Value::Bool(false));
+            }
+        },
+        false => quote! {},
+    };
 
+    // Keys are inserted in a fixed order: `type`, `title`, `description`, `properties`,
+    // `required`, then the keywords of `#[json_schema(...)]`.
     quote! {{
         let mut map = serde_json::Map::new();
         map.insert("type".into(), serde_json::Value::String("object".into()));
+        map.insert("title".into(), serde_json::Value::String(#title.into()));
+        #description
 
-        let (required, properties) = #generate_field_properties;
+        let (required, properties, additional_properties, all_of) = #generate_field_properties;
 
-        map.insert("required".into(), serde_json::Value::Array(required));
         map.insert("properties".into(), serde_json::Value::Object(properties));
+        if !required.is_empty() || !config.omit_empty_required {
+            map.insert("required".into(), serde_json::Value::Array(required));
+        }
+        if let Some(additional_properties) = additional_properties {
+            map.insert("additionalProperties".into(), additional_properties);
+        } #deny_unknown_fields
 
         #( map.insert(#attributes); )*
+        #merge_all_of
 
         serde_json::Value::Object(map)
     }}
@@ -74,7 +312,7 @@ fn struct_unnamed(fields: &FieldsUnnamed, attrs: &[Attribute]) -> proc_macro2::T
         let field_attributes = parse_attributes(&field.attrs);
         let attributes = parse_attributes(attrs);
         quote! {{
-            let mut schema = <#ty>::json_schema();
+            let mut schema = <#ty>::json_schema_in(config, defs);
             if let serde_json::Value::Object(map) = &mut schema {
                 #( map.insert(#attributes); )*
                 #( map.insert(#field_attributes); )*
@@ -83,18 +321,40 @@ fn struct_unnamed(fields: &FieldsUnnamed, attrs: &[Attribute]) -> proc_macro2::T
         }}
     } else {
         let attributes = parse_attributes(attrs);
+        // Either keyword replaces the closing one of the targeted draft and `maxItems`, so
+        // a tuple is opened with `additionalItems = true` or `unevaluatedItems = true` alike.
+        let schema_attrs = SchemaAttributes::try_from_attributes(attrs).unwrap_or_default();
+        let closed = !schema_attrs
+            .keys
+            .iter()
+            .any(|key| key == "additionalItems" || key == "unevaluatedItems");
         let items = fields.unnamed.iter().map(field_schema);
         let items_count = items.len();
         quote! {{
             let mut map = serde_json::Map::new();
             map.insert("type".into(), serde_json::Value::String("array".into()));
             map.insert("minItems".into(), serde_json::Value::Number(#count.into()));
-            map.insert("maxItems".into(), serde_json::Value::Number(#count.into()));
-            map.insert("unevaluatedItems".into(), serde_json::Value::Bool(false));
+            if #closed {
+                map.insert("maxItems".into(), serde_json::Value::Number(#count.into()));
+            }
 
-            let mut prefixItems = Vec::with_capacity(#items_count);
-            #( prefixItems.push(#items); )*
-            map.insert("prefixItems".into(), serde_json::Value::Array(prefixItems));
+            let mut prefix_items = json_schema_derive::__private::Vec::with_capacity(#items_count);
+            #( prefix_items.push(#items); )*
+            // Draft 2020-12 replaced the array form of `items` with `prefixItems`.
+            match config.draft {
+                json_schema_derive::Draft::Draft202012 => {
+                    map.insert("prefixItems".into(), serde_json::Value::Array(prefix_items));
+                    if #closed {
+                        map.insert("unevaluatedItems".into(), serde_json::Value::Bool(false));
+                    }
+                }
+                _ => {
+                    map.insert("items".into(), serde_json::Value::Array(prefix_items));
+                    if #closed {
+                        map.insert("additionalItems".into(), serde_json::Value::Bool(false));
+                    }
+                }
+            }
 
             #( map.insert(#attributes); )*
 
@@ -103,35 +363,208 @@ fn struct_unnamed(fields: &FieldsUnnamed, attrs: &[Attribute]) -> proc_macro2::T
     }
 }
 
-fn data_enum(data: &DataEnum, attrs: &[Attribute]) -> proc_macro2::TokenStream {
+fn data_enum(data: &DataEnum, attrs: &[Attribute], ident: &Ident) -> proc_macro2::TokenStream {
+    let all_variants_unit_type = data
+        .variants
+        .iter()
+        .all(|v| matches!(v.fields, Fields::Unit));
+
+    let schema_attrs = match SchemaAttributes::try_from_attributes(attrs) {
+        Ok(schema_attrs) => schema_attrs,
+        Err(err) => return err.to_compile_error(),
+    };
+    if let Some(repr) = &schema_attrs.repr {
+        if repr.value() != "int" {
+            return Error::new_spanned(repr, "expected `repr = \"int\"`").to_compile_error();
+        }
+        if !all_variants_unit_type {
+            return Error::new_spanned(repr, "`repr = \"int\"` can only be used on unit enums")
+                .to_compile_error();
+        }
+        return enum_int(data.variants.iter(), attrs, ident);
+    }
+    if let Some(representation) = &schema_attrs.representation {
+        if representation.value() != "array_of_variants" {
+            return Error::new_spanned(representation, "expected `as = \"array_of_variants\"`")
+                .to_compile_error();
+        }
+        if !all_variants_unit_type {
+            return Error::new_spanned(
+                representation,
+                "`as = \"array_of_variants\"` can only be used on unit enums",
+            )
+            .to_compile_error();
+        }
+        return enum_flags(data.variants.iter(), attrs, ident);
+    }
+
     #[cfg(feature = "serde-compat")]
     if let Some(s) = serde_compat::serde_data_enum(data, attrs) {
         return s;
     }
 
-    let all_variants_unit_type = data
-        .variants
-        .iter()
-        .all(|v| matches!(v.fields, Fields::Unit));
+    let schema = match all_variants_unit_type {
+        true => enum_unit(data.variants.iter(), attrs, ident),
+        false => enum_complex(data.variants.iter(), attrs, ident),
+    };
+    if !schema_attrs.variant_examples {
+        return schema;
+    }
+    let examples: Vec<_> = match data.variants.iter().map(variant_example).collect() {
+        Ok(examples) => examples,
+        Err(err) => return err.to_compile_error(),
+    };
+    quote! {{
+        let mut schema = #schema;
+        if let serde_json::Value::Object(map) = &mut schema {
+            let examples: json_schema_derive::__private::Vec<serde_json::Value> = json_schema_derive::__private::vec![#(#examples),*];
+            map.insert("examples".into(), serde_json::Value::Array(examples));
+        }
+        schema
+    }}
+}
 
-    match all_variants_unit_type {
-        true => enum_unit(data.variants.iter(), attrs),
-        false => enum_complex(data.variants.iter(), attrs),
+/// The name serde writes for a variant, which `#[serde(rename = "...")]` replaces.
+pub(crate) fn variant_name(variant: &Variant) -> proc_macro2::TokenStream {
+    #[cfg(feature = "serde-compat")]
+    if let Some(rename) = serde_compat::rename(&variant.attrs) {
+        return rename;
     }
+    let name = variant.ident.to_string();
+    quote! { #name }
+}
+
+/// A minimal instance of a unit or scalar newtype variant, for `variant_examples`.
+fn variant_example(variant: &Variant) -> Result<proc_macro2::TokenStream, Error> {
+    let name = variant_name(variant);
+    let field = match &variant.fields {
+        Fields::Unit => return Ok(quote! { serde_json::Value::String(#name.into()) }),
+        Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => unnamed.unnamed.first(),
+        _ => None,
+    };
+    let value = match field.and_then(|field| json_type(&field.ty)) {
+        Some(JsonType::String) => {
+            quote! { serde_json::Value::String(json_schema_derive::__private::String::new()) }
+        }
+        Some(JsonType::Number) => quote! { serde_json::Value::from(0) },
+        Some(JsonType::Boolean) => quote! { serde_json::Value::Bool(false) },
+        _ => {
+            return Err(Error::new_spanned(
+                variant,
+                "`variant_examples` only supports unit and scalar newtype variants",
+            ))
+        }
+    };
+    Ok(quote! {{
+        let mut example = serde_json::Map::new();
+        example.insert(#name.into(), #value);
+        serde_json::Value::Object(example)
+    }})
 }
 
 fn enum_unit<'a>(
     variants: impl Iterator<Item = &'a Variant>,
     attrs: &[Attribute],
+    ident: &Ident,
 ) -> proc_macro2::TokenStream {
     let attributes = parse_attributes(attrs);
-    let variants = variants.into_iter().map(|v| v.ident.to_string());
+    let variants: Vec<_> = variants.into_iter().collect();
+    let title = ident.to_string();
+    let annotated = variants.iter().any(|v| {
+        v.attrs
+            .iter()
+            .any(|attr| attr.path().is_ident("doc") || attr.path().is_ident("json_schema"))
+    });
+    // Documented or annotated values become a `oneOf` of constants so each can carry its
+    // own keywords.
+    let values = match annotated {
+        true => {
+            let values = variants.iter().map(|v| {
+                let name = variant_name(v);
+                let attributes = parse_attributes(&v.attrs);
+                quote! {{
+                    let mut value = serde_json::Map::new();
+                    value.insert("const".into(), serde_json::Value::String(#name.into()));
+                    #( value.insert(#attributes); )*
+                    serde_json::Value::Object(value)
+                }}
+            });
+            quote! {
+                let one_of: json_schema_derive::__private::Vec<serde_json::Value> = json_schema_derive::__private::vec![#(#values),*];
+                map.insert("oneOf".into(), serde_json::Value::Array(one_of));
+            }
+        }
+        false => {
+            let values = variants.iter().map(|v| variant_name(v));
+            quote! {
+                let mut enum_values: json_schema_derive::__private::Vec<serde_json::Value> = json_schema_derive::__private::Vec::new();
+                #( enum_values.push(#values.into()); )*
+                map.insert("enum".into(), serde_json::Value::Array(enum_values));
+            }
+        }
+    };
     quote! {{
         let mut map = serde_json::Map::new();
         map.insert("type".into(), serde_json::Value::String("string".into()));
-        let mut enum_values: Vec<serde_json::Value> = Vec::new();
-        #( enum_values.push(#variants.into()); )*
+        map.insert("title".into(), serde_json::Value::String(#title.into()));
+        #values
+        #( map.insert(#attributes); )*
+        serde_json::Value::Object(map)
+    }}
+}
+
+/// Unit enum serialized as its discriminants, e.g. with `serde_repr`.
+fn enum_int<'a>(
+    variants: impl Iterator<Item = &'a Variant>,
+    attrs: &[Attribute],
+    ident: &Ident,
+) -> proc_macro2::TokenStream {
+    let attributes = parse_attributes(attrs);
+    // Casting the variants yields implicit discriminants as well as explicit ones.
+    let variants = variants.into_iter().map(|v| &v.ident);
+    let title = ident.to_string();
+    quote! {{
+        let mut map = serde_json::Map::new();
+        let ty = match config.integer_type {
+            true => "integer",
+            false => "number",
+        };
+        map.insert("type".into(), serde_json::Value::String(ty.into()));
+        map.insert("title".into(), serde_json::Value::String(#title.into()));
+        let discriminants: json_schema_derive::__private::Vec<i64> = json_schema_derive::__private::vec![#(Self::#variants as i64),*];
+        let enum_values = discriminants.iter().copied().map(serde_json::Value::from).collect();
         map.insert("enum".into(), serde_json::Value::Array(enum_values));
+        if config.infer_integer_bounds {
+            if let Some(minimum) = discriminants.iter().min() {
+                map.insert("minimum".into(), (*minimum).into());
+            }
+            if let Some(maximum) = discriminants.iter().max() {
+                map.insert("maximum".into(), (*maximum).into());
+            }
+        }
+        #( map.insert(#attributes); )*
+        serde_json::Value::Object(map)
+    }}
+}
+
+/// Unit enum whose values are serialized as a set of variant names, e.g. flags.
+fn enum_flags<'a>(
+    variants: impl Iterator<Item = &'a Variant>,
+    attrs: &[Attribute],
+    ident: &Ident,
+) -> proc_macro2::TokenStream {
+    let attributes = parse_attributes(attrs);
+    let values = variants.into_iter().map(|v| v.ident.to_string());
+    let title = ident.to_string();
+    quote! {{
+        let mut map = serde_json::Map::new();
+        map.insert("type".into(), serde_json::Value::String("array".into()));
+        map.insert("title".into(), serde_json::Value::String(#title.into()));
+        map.insert(
+            "items".into(),
+            serde_json::json!({ "type": "string", "enum": [#(#values),*] }),
+        );
+        map.insert("uniqueItems".into(), serde_json::Value::Bool(true));
         #( map.insert(#attributes); )*
         serde_json::Value::Object(map)
     }}
@@ -140,44 +573,87 @@ fn enum_unit<'a>(
 fn enum_complex<'a>(
     variants: impl Iterator<Item = &'a Variant>,
     attrs: &[Attribute],
+    ident: &Ident,
 ) -> proc_macro2::TokenStream {
     let attributes = parse_attributes(attrs);
+    let title = ident.to_string();
+    // serde writes a unit variant as its name and any other variant as an object
+    // holding only the variant name as key.
     let variants = variants.into_iter().map(|v| {
-        let ident = &v.ident.to_string();
+        let ident = &variant_name(v);
         let inner = match &v.fields {
-            Fields::Named(named) => struct_named(named, &v.attrs),
+            Fields::Named(named) => struct_named(named, &v.attrs, &v.ident),
             Fields::Unnamed(unnamed) => struct_unnamed(unnamed, &v.attrs),
-            Fields::Unit => Error::new_spanned(&v.ident, "Unit variants are not yet supported")
-                .to_compile_error(),
+            Fields::Unit => {
+                return quote! {
+                    serde_json::json!({ "type": "string", "const": #ident })
+                }
+            }
         };
-        quote! {
+        quote! {{
+            let mut properties = serde_json::Map::new();
             properties.insert(#ident.into(), #inner);
-        }
+            let mut variant = serde_json::Map::new();
+            variant.insert("type".into(), serde_json::Value::String("object".into()));
+            variant.insert("properties".into(), serde_json::Value::Object(properties));
+            variant.insert("required".into(), serde_json::json!([#ident]));
+            variant.insert("additionalProperties".into(), serde_json::Value::Bool(false));
+            serde_json::Value::Object(variant)
+        }}
     });
     quote! {{
         let mut map = serde_json::Map::new();
-        map.insert("type".into(), serde_json::Value::String("object".into()));
-        let mut properties = serde_json::Map::new();
-        #(#variants;)*;
-        map.insert("properties".into(), serde_json::Value::Object(properties));
+        map.insert("title".into(), serde_json::Value::String(#title.into()));
+        let one_of: json_schema_derive::__private::Vec<serde_json::Value> = json_schema_derive::__private::vec![#(#variants),*];
+        map.insert("oneOf".into(), serde_json::Value::Array(one_of));
         #( map.insert(#attributes); )*
         serde_json::Value::Object(map)
     }}
 }
 
 // Utilities
-pub(crate) fn field_props(fields: &FieldsNamed) -> proc_macro2::TokenStream {
+/// How the fields of a struct or struct variant are named in the schema.
+#[derive(Default)]
+pub(crate) struct FieldNaming {
+    /// Prefix prepended to every property name.
+    pub(crate) prefix: String,
+    /// Case applied to the names of fields that are not renamed explicitly.
+    #[cfg(feature = "serde-compat")]
+    pub(crate) rename_all: Option<serde_compat::RenameRule>,
+}
+
+impl FieldNaming {
+    fn from_attributes(attrs: &[Attribute]) -> Self {
+        let schema_attrs = SchemaAttributes::try_from_attributes(attrs).unwrap_or_default();
+        Self {
+            prefix: schema_attrs.rename_prefix(),
+            #[cfg(feature = "serde-compat")]
+            rename_all: serde_compat::rename_all(attrs),
+        }
+    }
+}
+
+pub(crate) fn field_props(fields: &FieldsNamed, naming: &FieldNaming) -> proc_macro2::TokenStream {
     let inner = fields.named.iter().map(|field| {
+        let schema_attrs = SchemaAttributes::try_from_attributes(&field.attrs).unwrap_or_default();
+        if schema_attrs.skip || is_phantom_data(&field.ty) {
+            return quote! {};
+        }
+        if schema_attrs.flatten {
+            return flatten_field(field);
+        }
+
         #[cfg(feature = "serde-compat")]
-        if let Some(serde_field) = serde_compat::serde_field(field) {
+        if let Some(serde_field) = serde_compat::serde_field(field, naming) {
             return serde_field;
         }
 
-        let name = field.ident.as_ref().unwrap().to_string();
+        // serde names raw identifier fields such as `r#type` without the `r#` prefix.
+        let name = format!("{}{}", naming.prefix, field.ident.as_ref().unwrap().unraw());
         let schema = field_schema(field);
-        let required = match is_option(&field.ty) {
-            true => quote! {},
-            false => quote! { required.push(#name.into()); },
+        let required = match is_required(field) {
+            true => quote! { required.push(#name.into()); },
+            false => quote! {},
         };
 
         quote! {
@@ -188,19 +664,237 @@ pub(crate) fn field_props(fields: &FieldsNamed) -> proc_macro2::TokenStream {
     });
 
     quote! {{
-        let mut required: Vec<serde_json::Value> = Vec::new();
+        let mut required: json_schema_derive::__private::Vec<serde_json::Value> = json_schema_derive::__private::Vec::new();
         let mut properties = serde_json::Map::new();
+        let mut additional_properties: Option<serde_json::Value> = None;
+        let mut all_of: json_schema_derive::__private::Vec<serde_json::Value> = json_schema_derive::__private::Vec::new();
         #(#inner;)*
-        (required, properties)
+        (required, properties, additional_properties, all_of)
     }}
 }
 
+/// Whether the field is flattened with `#[json_schema(flatten)]` or `#[serde(flatten)]`.
+pub(crate) fn is_flattened(field: &Field) -> bool {
+    let schema_attrs = SchemaAttributes::try_from_attributes(&field.attrs).unwrap_or_default();
+    #[cfg(feature = "serde-compat")]
+    if serde_compat::is_flattened(field) {
+        return true;
+    }
+    schema_attrs.flatten
+}
+
+/// Appends the `all_of` schemas collected by `field_props` to the `allOf` of `map`.
+pub(crate) fn merge_all_of() -> proc_macro2::TokenStream {
+    quote! {
+        if !all_of.is_empty() {
+            match map.get_mut("allOf") {
+                Some(serde_json::Value::Array(existing)) => existing.extend(all_of),
+                _ => {
+                    map.insert("allOf".into(), serde_json::Value::Array(all_of));
+                }
+            }
+        }
+    }
+}
+
+/// Merges the schema of a flattened field into the properties of the parent object.
+pub(crate) fn flatten_field(field: &Field) -> proc_macro2::TokenStream {
+    let schema = field_schema(field);
+    // None of the keys of an optional flattened value have to be present.
+    let extend_required = match is_required(field) {
+        false => quote! {},
+        true => quote! {
+            if let Some(serde_json::Value::Array(inner_required)) = inner.remove("required") {
+                if !inner_required.is_empty() {
+                    required.extend(inner_required);
+                }
+            }
+        },
+    };
+    // The variants of a flattened enum are added to the `allOf` of the parent object,
+    // which closes them instead. An optional flattened enum contributes nothing.
+    let merge_variants = match is_required(field) {
+        false => quote! {},
+        true => quote! {
+            else if let Some(serde_json::Value::Array(variants)) = inner.remove("oneOf") {
+                let variants: json_schema_derive::__private::Vec<_> = variants
+                    .into_iter()
+                    .map(|mut variant| {
+                        if let serde_json::Value::Object(variant) = &mut variant {
+                            if variant.get("additionalProperties") == Some(&serde_json::Value::Bool(false)) {
+                                variant.remove("additionalProperties");
+                            }
+                        }
+                        variant
+                    })
+                    .collect();
+                all_of.push(serde_json::json!({ "oneOf": variants }));
+            }
+        },
+    };
+    // A missing optional value writes no keys rather than `null`, so only the non-null
+    // branch of a nullable `anyOf` is merged.
+    let unwrap_nullable = match is_option(&field.ty) {
+        false => quote! {},
+        true => quote! {
+            let schema = match schema {
+                serde_json::Value::Object(mut map) if map.len() == 1 => match map.remove("anyOf") {
+                    Some(serde_json::Value::Array(branches)) => branches
+                        .into_iter()
+                        .find(|branch| branch.get("type").and_then(serde_json::Value::as_str) != Some("null"))
+                        .unwrap_or_default(),
+                    Some(other) => {
+                        map.insert("anyOf".into(), other);
+                        serde_json::Value::Object(map)
+                    }
+                    None => serde_json::Value::Object(map),
+                },
+                schema => schema,
+            };
+        },
+    };
+    // A flattened map catches every key not matched by a property, so its value
+    // schema becomes the `additionalProperties` of the parent object.
+    quote! {
+        // Only the keywords of the flattened schema are merged, so it is never a `$ref`.
+        let schema = {
+            let config = &json_schema_derive::JsonSchemaConfig {
+                inline: true,
+                ..config.clone()
+            };
+            #schema
+        };
+        #unwrap_nullable
+        if let serde_json::Value::Object(mut inner) = schema {
+            #extend_required
+            match inner.remove("properties") {
+                Some(serde_json::Value::Object(inner_properties)) => {
+                    properties.extend(inner_properties);
+                }
+                Some(_) => {}
+                None => {
+                    if let Some(value) = inner.remove("additionalProperties") {
+                        additional_properties = Some(value);
+                    } #merge_variants
+                }
+            }
+        }
+    }
+}
+
 pub(crate) fn field_schema(field: &Field) -> proc_macro2::TokenStream {
     let ty = &field.ty;
     let attributes = parse_attributes(&field.attrs);
+    let schema_attrs = match SchemaAttributes::try_from_attributes(&field.attrs) {
+        Ok(schema_attrs) => schema_attrs,
+        Err(err) => return err.to_compile_error(),
+    };
+    #[cfg(feature = "serde-compat")]
+    let serde_with = serde_compat::serde_with(field);
+    #[cfg(not(feature = "serde-compat"))]
+    let serde_with: Option<proc_macro2::TokenStream> = None;
+    // An explicit schema takes precedence, so the field type need not implement `JsonSchema`.
+    let schema = match (&schema_attrs.schema, &schema_attrs.with) {
+        (Some(schema), Some(_)) => {
+            return Error::new_spanned(schema, "`schema` and `with` cannot be used together")
+                .to_compile_error()
+        }
+        (Some(schema), None) => {
+            if let Err(err) = serde_json::from_str::<serde_json::Value>(&schema.value()) {
+                return Error::new_spanned(schema, format!("invalid JSON schema: {err}"))
+                    .to_compile_error();
+            }
+            quote! {
+                serde_json::from_str::<serde_json::Value>(#schema).unwrap()
+            }
+        }
+        (None, Some(with)) => quote! { #with() },
+        // The serialized form no longer follows the field type, so any value is accepted
+        // unless `bytes` describes it. Using a deprecated constant is the only way to raise
+        // a warning on stable.
+        (None, None) if serde_with.is_some() && schema_attrs.bytes.is_none() => {
+            let span = serde_with
+                .to_token_stream()
+                .into_iter()
+                .next()
+                .map_or_else(|| ty.span(), |token| token.span());
+            let warning = Ident::new("serde_with_field", span);
+            quote_spanned! {span=> {
+                #[deprecated(
+                    note = "`#[serde(with = ...)]` changes the serialized form, so the field accepts any value; describe it with `#[json_schema(with = ...)]` or `#[json_schema(schema = ...)]`"
+                )]
+                #[allow(non_upper_case_globals)]
+                const #warning: () = ();
+                let () = #warning;
+                serde_json::json!({})
+            }}
+        }
+        (None, None) => {
+            if let Err(err) = check_keywords(ty, &schema_attrs) {
+                return err.to_compile_error();
+            }
+            match (&schema_attrs.bytes, schema_attrs.inline) {
+                (Some(bytes), _) if bytes.value() != "base64" => {
+                    return Error::new_spanned(bytes, "expected `bytes = \"base64\"`")
+                        .to_compile_error()
+                }
+                // The bytes are serialized as a base64 string instead of an array of numbers.
+                (Some(_), _) => quote! {
+                    serde_json::json!({ "type": "string", "contentEncoding": "base64" })
+                },
+                (None, Some(inline)) => quote! {{
+                    let config = json_schema_derive::JsonSchemaConfig {
+                        inline: #inline,
+                        ..config.clone()
+                    };
+                    <#ty>::json_schema_in(&config, defs)
+                }},
+                (None, None) => quote! { <#ty>::json_schema_in(config, defs) },
+            }
+        }
+    };
+    // A fixed set of literals may mix types, so it replaces the type of the field.
+    let remove_type = match schema_attrs.enum_values {
+        true => quote! { map.remove("type"); },
+        false => quote! {},
+    };
+    // A nullable `anyOf` wrapper has no `type`, so its non-null branch is rewritten instead.
+    let integer_type = match schema_attrs.integer {
+        true => quote! {
+            if let Some(serde_json::Value::Array(branches)) = map.get_mut("anyOf") {
+                for branch in branches.iter_mut().filter_map(serde_json::Value::as_object_mut) {
+                    if branch.get("type").is_some_and(|ty| ty != "null") {
+                        branch.insert("type".into(), serde_json::Value::String("integer".into()));
+                    }
+                }
+            } else if map.contains_key("type") {
+                map.insert("type".into(), serde_json::Value::String("integer".into()));
+            }
+        },
+        false => quote! {},
+    };
+    let property_names = match schema_attrs.property_names.is_empty() {
+        true => quote! {},
+        false => {
+            let (keys, values): (Vec<_>, Vec<_>) =
+                schema_attrs.property_names.iter().cloned().unzip();
+            quote! {
+                let mut property_names = serde_json::Map::new();
+                #( property_names.insert(#keys.into(), serde_json::to_value(#values).unwrap()); )*
+                map.insert("propertyNames".into(), serde_json::Value::Object(property_names));
+            }
+        }
+    };
+    let schema = match is_option(ty) {
+        true => quote! { config.nullable(#schema) },
+        false => schema,
+    };
     quote! {{
-        let mut schema = <#ty>::json_schema();
+        let mut schema = #schema;
         if let serde_json::Value::Object(map) = &mut schema {
+            #remove_type
+            #integer_type
+            #property_names
             #( map.insert(#attributes); )*
         }
         schema
@@ -210,44 +904,284 @@ pub(crate) fn field_schema(field: &Field) -> proc_macro2::TokenStream {
 pub(crate) fn parse_attributes(
     attrs: &[Attribute],
 ) -> impl Iterator<Item = proc_macro2::TokenStream> + '_ {
-    attrs
+    let description = doc_description(attrs)
+        .map(|description| quote! { "description".into(), #description })
+        .into_iter();
+    let keywords = attrs
         .iter()
         .filter_map(|attr| {
-            if attr.path().is_ident("doc") {
-                let meta_list = attr.meta.require_name_value().ok()?;
-                let val = &meta_list.value;
-                return Some(vec![
-                    quote! { "description".into(), serde_json::to_value(#val.trim()).unwrap() },
-                ]);
-            }
             if attr.path().is_ident("json_schema") {
-                let meta_list = attr.meta.require_list().ok()?;
-                let pairs = meta_list
-                    .parse_args_with(Punctuated::<MetaNameValue, Token![,]>::parse_terminated)
-                    .ok()?;
+                let metas = attributes::parse_metas(attr).ok()?;
+                // String literal keys, such as `"x-foo"`, are emitted verbatim.
+                let literal_keys = attributes::parse_literal_keys(attr).ok()?;
+                // Reserved flags such as `skip` are handled by `SchemaAttributes`.
                 return Some(
-                    pairs
+                    metas
                         .into_iter()
-                        .filter_map(|pair| {
-                            let key = pair.path.get_ident()?.to_string();
-                            let val = &pair.value;
-                            Some(quote! { (#key).into(), serde_json::to_value(#val).unwrap() })
+                        .filter_map(|meta| {
+                            let key = match meta.path().get_ident()?.unraw().to_string() {
+                                key if attributes::is_reserved(&key) => return None,
+                                // The bare `required` flag is handled by `SchemaAttributes`.
+                                key if key == "required" && matches!(meta, Meta::Path(_)) => {
+                                    return None
+                                }
+                                key if key == "enum_values" => "enum".to_string(),
+                                key => key,
+                            };
+                            let val = match &meta {
+                                Meta::NameValue(pair) => attribute_value(&key, &pair.value),
+                                // Other bare flags, such as `deprecated`, set their keyword.
+                                Meta::Path(_) => quote! { serde_json::Value::Bool(true) },
+                                // Nested lists, such as `dependentRequired(a = ["b"])`,
+                                // become objects.
+                                Meta::List(list) => {
+                                    let nested = list
+                                        .parse_args_with(
+                                            Punctuated::<Meta, Token![,]>::parse_terminated,
+                                        )
+                                        .ok()?;
+                                    let entries = nested
+                                        .iter()
+                                        .map(|meta| {
+                                            let pair = meta.require_name_value().ok()?;
+                                            let key = pair.path.get_ident()?.unraw().to_string();
+                                            let val = attribute_value(&key, &pair.value);
+                                            Some(quote! { object.insert(#key.into(), #val); })
+                                        })
+                                        .collect::<Option<Vec<_>>>()?;
+                                    quote! {{
+                                        let mut object = serde_json::Map::new();
+                                        #(#entries)*
+                                        serde_json::Value::Object(object)
+                                    }}
+                                }
+                            };
+                            // A single `example` is added to the `examples` array, which
+                            // every supported draft defines.
+                            if key == "example" {
+                                return Some(quote! {
+                                    "examples".into(), serde_json::Value::Array(json_schema_derive::__private::vec![#val])
+                                });
+                            }
+                            Some(quote! { (#key).into(), #val })
                         })
-                        .collect(),
+                        .chain(literal_keys.into_iter().map(|(key, value)| {
+                            let val = attribute_value(&key.value(), &value);
+                            quote! { (#key).into(), #val }
+                        }))
+                        .collect::<Vec<_>>(),
                 );
             }
             None
         })
-        .flatten()
+        .flatten();
+    description.chain(keywords)
 }
 
-pub(crate) fn is_option(ty: &Type) -> bool {
+/// The `description` taken from the doc comment of an item.
+///
+/// Every line of the comment is trimmed and the lines are joined with newlines.
+fn doc_description(attrs: &[Attribute]) -> Option<proc_macro2::TokenStream> {
+    let lines: Vec<_> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| attr.meta.require_name_value().ok())
+        .map(|doc| &doc.value)
+        .collect();
+    if lines.is_empty() {
+        return None;
+    }
+    Some(quote! {{
+        let mut description = json_schema_derive::__private::String::new();
+        for (index, line) in [#(#lines),*].into_iter().enumerate() {
+            if index > 0 {
+                description.push('\n');
+            }
+            description.push_str(line.trim());
+        }
+        serde_json::Value::String(description)
+    }})
+}
+
+/// Converts the value of a `#[json_schema(key = value)]` pair into a `serde_json::Value`.
+fn attribute_value(key: &str, value: &Expr) -> proc_macro2::TokenStream {
+    // `contains = "Type"` names a type whose schema array items must contain.
+    if key == "contains" {
+        if let Expr::Lit(ExprLit {
+            lit: Lit::Str(lit), ..
+        }) = value
+        {
+            if let Ok(ty) = lit.parse::<Type>() {
+                return quote! { <#ty>::json_schema_in(config, defs) };
+            }
+        }
+    }
+    // `allOf = ["Base"]` and the other combinators name types, which are referenced from
+    // `$defs` even when the config inlines nested types.
+    if let ("allOf" | "anyOf" | "oneOf", Expr::Array(array)) = (key, value) {
+        let items = array.elems.iter().map(|item| match item {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(lit), ..
+            }) => match lit.parse::<Type>() {
+                Ok(ty) => quote! {{
+                    let config = json_schema_derive::JsonSchemaConfig {
+                        inline: false,
+                        ..config.clone()
+                    };
+                    <#ty>::json_schema_in(&config, defs)
+                }},
+                Err(err) => err.to_compile_error(),
+            },
+            item => attribute_value("", item),
+        });
+        return quote! { serde_json::Value::Array(json_schema_derive::__private::vec![#(#items),*]) };
+    }
+    // Array literals are converted element by element so they may mix types.
+    if let Expr::Array(array) = value {
+        let items = array.elems.iter().map(|item| attribute_value("", item));
+        return quote! { serde_json::Value::Array(json_schema_derive::__private::vec![#(#items),*]) };
+    }
+    quote! { serde_json::to_value(#value).unwrap() }
+}
+
+/// Rejects keywords that cannot apply to the JSON type produced by `ty`.
+fn check_keywords(ty: &Type, attrs: &SchemaAttributes) -> Result<(), Error> {
+    let has_key = |name: &str| attrs.keys.iter().find(|key| *key == name);
+    if let (Some(_), Some(write_only)) = (has_key("readOnly"), has_key("writeOnly")) {
+        return Err(Error::new_spanned(
+            write_only,
+            "`readOnly` and `writeOnly` cannot be used together",
+        ));
+    }
+
+    let Some(actual) = json_type(ty) else {
+        return Ok(());
+    };
+    for key in &attrs.keys {
+        let expected = match key.to_string().as_str() {
+            "minItems" | "maxItems" | "uniqueItems" | "bytes" => JsonType::Array,
+            "key_min_length"
+            | "key_max_length"
+            | "propertyNames_pattern"
+            | "minProperties"
+            | "maxProperties" => JsonType::Object,
+            "contentEncoding" | "contentMediaType" => JsonType::String,
+            "integer" => JsonType::Number,
+            _ => continue,
+        };
+        if expected != actual {
+            return Err(Error::new_spanned(
+                key,
+                format!("`{key}` can only be used on {expected} fields"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// JSON types a Rust type can be recognised to produce from its syntax alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum JsonType {
+    Array,
+    Object,
+    String,
+    Number,
+    Boolean,
+    Null,
+}
+
+impl std::fmt::Display for JsonType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            JsonType::Array => "array",
+            JsonType::Object => "object",
+            JsonType::String => "string",
+            JsonType::Number => "number",
+            JsonType::Boolean => "boolean",
+            JsonType::Null => "null",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The JSON type produced by `ty`, or `None` for types that are not known to the macro.
+pub(crate) fn json_type(ty: &Type) -> Option<JsonType> {
+    match ty {
+        Type::Array(_) | Type::Slice(_) => Some(JsonType::Array),
+        Type::Tuple(tuple) if tuple.elems.is_empty() => Some(JsonType::Null),
+        Type::Tuple(_) => Some(JsonType::Array),
+        Type::Reference(reference) => json_type(&reference.elem),
+        Type::Paren(paren) => json_type(&paren.elem),
+        Type::Group(group) => json_type(&group.elem),
+        Type::Path(type_path) => {
+            let last = type_path.path.segments.last()?;
+            match last.ident.to_string().as_str() {
+                "Option" | "Box" | "Cow" => match &last.arguments {
+                    PathArguments::AngleBracketed(args) => {
+                        args.args.iter().find_map(|arg| match arg {
+                            GenericArgument::Type(ty) => json_type(ty),
+                            _ => None,
+                        })
+                    }
+                    _ => None,
+                },
+                "Vec" | "VecDeque" | "HashSet" | "BTreeSet" => Some(JsonType::Array),
+                "HashMap" | "BTreeMap" => Some(JsonType::Object),
+                "String" | "str" => Some(JsonType::String),
+                "bool" => Some(JsonType::Boolean),
+                "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16" | "i32" | "i64"
+                | "i128" | "isize" | "f32" | "f64" => Some(JsonType::Number),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+pub(crate) fn is_phantom_data(ty: &Type) -> bool {
     if let Type::Path(type_path) = ty {
         if let Some(last) = type_path.path.segments.last() {
-            if last.ident == "Option" {
-                return true;
-            }
+            return last.ident == "PhantomData";
         }
     }
     false
 }
+
+/// Whether the field must be present: `#[json_schema(required)]` and `optional` take
+/// precedence over the type, which is only recognised as `Option` syntactically.
+pub(crate) fn is_required(field: &Field) -> bool {
+    let schema_attrs = SchemaAttributes::try_from_attributes(&field.attrs).unwrap_or_default();
+    schema_attrs
+        .required
+        .unwrap_or_else(|| !is_option(&field.ty))
+}
+
+/// Whether `ty` is `Option<T>`, written as `Option`, `std::option::Option` or
+/// `core::option::Option`. Other paths ending in `Option` name user types.
+pub(crate) fn is_option(ty: &Type) -> bool {
+    let Type::Path(type_path) = ty else {
+        return false;
+    };
+    if type_path.qself.is_some() {
+        return false;
+    }
+    let segments: Vec<_> = type_path
+        .path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect();
+    let is_std_path = match segments.as_slice() {
+        [option] => option == "Option",
+        [krate, module, option] => {
+            (krate == "std" || krate == "core") && module == "option" && option == "Option"
+        }
+        _ => false,
+    };
+    let has_argument = type_path
+        .path
+        .segments
+        .last()
+        .is_some_and(|last| matches!(last.arguments, PathArguments::AngleBracketed(_)));
+    is_std_path && has_argument
+}
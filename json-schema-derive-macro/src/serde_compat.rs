@@ -1,11 +1,93 @@
 use quote::{quote, ToTokens};
-use syn::{punctuated::Punctuated, Attribute, DataEnum, Error, Field, Fields, Meta, Token};
+use syn::{
+    ext::IdentExt, punctuated::Punctuated, Attribute, DataEnum, Error, Expr, ExprLit, Field,
+    Fields, Lit, Meta, Token,
+};
+
+use crate::attributes::SchemaAttributes;
+use crate::FieldNaming;
+
+/// Case conversion of `#[serde(rename_all = "...")]` and `rename_all_fields`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::enum_variant_names)]
+pub(crate) enum RenameRule {
+    LowerCase,
+    UpperCase,
+    PascalCase,
+    CamelCase,
+    SnakeCase,
+    ScreamingSnakeCase,
+    KebabCase,
+    ScreamingKebabCase,
+}
+
+impl RenameRule {
+    fn from_lit(value: &Expr) -> Result<Self, Error> {
+        let name = match value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(lit), ..
+            }) => lit.value(),
+            _ => return Err(Error::new_spanned(value, "expected a string literal")),
+        };
+        match name.as_str() {
+            "lowercase" => Ok(Self::LowerCase),
+            "UPPERCASE" => Ok(Self::UpperCase),
+            "PascalCase" => Ok(Self::PascalCase),
+            "camelCase" => Ok(Self::CamelCase),
+            "snake_case" => Ok(Self::SnakeCase),
+            "SCREAMING_SNAKE_CASE" => Ok(Self::ScreamingSnakeCase),
+            "kebab-case" => Ok(Self::KebabCase),
+            "SCREAMING-KEBAB-CASE" => Ok(Self::ScreamingKebabCase),
+            _ => Err(Error::new_spanned(value, "unknown rename rule")),
+        }
+    }
+
+    /// Rename a snake_case field name, as serde does.
+    pub(crate) fn apply_to_field(self, field: &str) -> String {
+        match self {
+            // Field names are assumed to be lowercase already, so serde leaves them as they
+            // are. `UPPERCASE` folds the case of the whole name without splitting it.
+            Self::LowerCase | Self::SnakeCase => field.to_owned(),
+            Self::UpperCase | Self::ScreamingSnakeCase => field.to_ascii_uppercase(),
+            Self::PascalCase => {
+                let mut pascal = String::new();
+                let mut capitalize = true;
+                for ch in field.chars() {
+                    if ch == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        pascal.push(ch.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        pascal.push(ch);
+                    }
+                }
+                pascal
+            }
+            Self::CamelCase => {
+                let pascal = Self::PascalCase.apply_to_field(field);
+                let mut chars = pascal.chars();
+                match chars.next() {
+                    Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+                    None => pascal,
+                }
+            }
+            Self::KebabCase => field.replace('_', "-"),
+            Self::ScreamingKebabCase => field.to_ascii_uppercase().replace('_', "-"),
+        }
+    }
+}
 
 #[derive(Debug, Default)]
 pub(crate) struct SerdeAttributes {
     pub(crate) skip: bool,
     pub(crate) flatten: bool,
+    pub(crate) deny_unknown_fields: bool,
     pub(crate) rename: Option<proc_macro2::TokenStream>,
+    pub(crate) with: Option<proc_macro2::TokenStream>,
+    pub(crate) aliases: Vec<proc_macro2::TokenStream>,
+    pub(crate) rename_all: Option<RenameRule>,
+    pub(crate) rename_all_fields: Option<RenameRule>,
     pub(crate) tag: Option<proc_macro2::TokenStream>,
 }
 
@@ -14,7 +96,12 @@ impl SerdeAttributes {
         let mut this = Self {
             skip: false,
             flatten: false,
+            deny_unknown_fields: false,
             rename: None,
+            with: None,
+            aliases: Vec::new(),
+            rename_all: None,
+            rename_all_fields: None,
             tag: None,
         };
         for attr in attrs {
@@ -32,10 +119,29 @@ impl SerdeAttributes {
                 if meta.path().is_ident("flatten") {
                     this.flatten = true;
                 }
+                if meta.path().is_ident("deny_unknown_fields") {
+                    this.deny_unknown_fields = true;
+                }
                 if meta.path().is_ident("rename") {
                     let name_value = meta.require_name_value()?;
                     this.rename = Some(name_value.value.to_token_stream());
                 }
+                if meta.path().is_ident("with") {
+                    let name_value = meta.require_name_value()?;
+                    this.with = Some(name_value.value.to_token_stream());
+                }
+                if meta.path().is_ident("alias") {
+                    let name_value = meta.require_name_value()?;
+                    this.aliases.push(name_value.value.to_token_stream());
+                }
+                if meta.path().is_ident("rename_all") {
+                    let name_value = meta.require_name_value()?;
+                    this.rename_all = Some(RenameRule::from_lit(&name_value.value)?);
+                }
+                if meta.path().is_ident("rename_all_fields") {
+                    let name_value = meta.require_name_value()?;
+                    this.rename_all_fields = Some(RenameRule::from_lit(&name_value.value)?);
+                }
                 if meta.path().is_ident("tag") {
                     let name_value = meta.require_name_value()?;
                     this.tag = Some(name_value.value.to_token_stream());
@@ -46,35 +152,91 @@ impl SerdeAttributes {
     }
 }
 
-pub(crate) fn serde_field(field: &Field) -> Option<proc_macro2::TokenStream> {
+/// Reports malformed `#[serde(...)]` attributes, which the helpers below treat as absent.
+pub(crate) fn check_attributes(attrs: &[Attribute]) -> Result<(), Error> {
+    SerdeAttributes::try_from_attributes(attrs).map(|_| ())
+}
+
+/// Whether the field has `#[serde(flatten)]`.
+pub(crate) fn is_flattened(field: &Field) -> bool {
+    SerdeAttributes::try_from_attributes(&field.attrs)
+        .unwrap_or_default()
+        .flatten
+}
+
+/// Whether the struct has `#[serde(deny_unknown_fields)]`.
+pub(crate) fn deny_unknown_fields(attrs: &[Attribute]) -> bool {
+    SerdeAttributes::try_from_attributes(attrs)
+        .unwrap_or_default()
+        .deny_unknown_fields
+}
+
+/// The `rename_all` rule of a struct or variant.
+pub(crate) fn rename_all(attrs: &[Attribute]) -> Option<RenameRule> {
+    SerdeAttributes::try_from_attributes(attrs)
+        .unwrap_or_default()
+        .rename_all
+}
+
+/// The name given by `#[serde(rename = "...")]`.
+pub(crate) fn rename(attrs: &[Attribute]) -> Option<proc_macro2::TokenStream> {
+    SerdeAttributes::try_from_attributes(attrs)
+        .unwrap_or_default()
+        .rename
+}
+
+/// The module of `#[serde(with = "...")]`, which changes how the field is serialized.
+pub(crate) fn serde_with(field: &Field) -> Option<proc_macro2::TokenStream> {
+    SerdeAttributes::try_from_attributes(&field.attrs)
+        .unwrap_or_default()
+        .with
+}
+
+pub(crate) fn serde_field(field: &Field, naming: &FieldNaming) -> Option<proc_macro2::TokenStream> {
     let serde_attrs = SerdeAttributes::try_from_attributes(&field.attrs).unwrap_or_default();
     if serde_attrs.skip {
         return Some(quote! {});
     }
 
-    let name = field.ident.as_ref().unwrap().to_string();
+    let name = field.ident.as_ref().unwrap().unraw().to_string();
+    let rename_prefix = &naming.prefix;
     let name = match &serde_attrs.rename {
-        Some(rename) => quote! { #rename },
-        None => quote! { #name },
+        Some(rename) if rename_prefix.is_empty() => quote! { #rename },
+        Some(rename) => quote! { concat!(#rename_prefix, #rename) },
+        None => {
+            let name = match naming.rename_all {
+                Some(rule) => rule.apply_to_field(&name),
+                None => name,
+            };
+            let name = format!("{rename_prefix}{name}");
+            quote! { #name }
+        }
     };
     let schema = super::field_schema(field);
-    let required = match super::is_option(&field.ty) {
-        true => quote! {},
-        false => quote! { required.push(#name.into()); },
+    // Other names serde accepts for the field when deserializing.
+    let schema = match serde_attrs.aliases.as_slice() {
+        [] => schema,
+        aliases => {
+            let aliases = aliases.iter().map(|alias| match rename_prefix.is_empty() {
+                true => quote! { #alias },
+                false => quote! { concat!(#rename_prefix, #alias) },
+            });
+            quote! {{
+                let mut schema = #schema;
+                if let serde_json::Value::Object(map) = &mut schema {
+                    map.insert("x-aliases".into(), serde_json::json!([#(#aliases),*]));
+                }
+                schema
+            }}
+        }
+    };
+    let required = match super::is_required(field) {
+        true => quote! { required.push(#name.into()); },
+        false => quote! {},
     };
 
     if serde_attrs.flatten {
-        return Some(quote! {
-            let schema = #schema;
-            if let serde_json::Value::Object(mut inner) = schema {
-                if let Some(serde_json::Value::Array(inner_required)) = inner.remove("required") {
-                    required.extend(inner_required);
-                }
-                if let Some(serde_json::Value::Object(inner_properties)) = inner.remove("properties") {
-                    properties.extend(inner_properties);
-                }
-            }
-        });
+        return Some(super::flatten_field(field));
     }
 
     Some(quote! {
@@ -83,21 +245,34 @@ pub(crate) fn serde_field(field: &Field) -> Option<proc_macro2::TokenStream> {
     })
 }
 
-pub(crate) fn serde_data_enum<'a>(
+pub(crate) fn serde_data_enum(
     data: &DataEnum,
     attrs: &[Attribute],
 ) -> Option<proc_macro2::TokenStream> {
-    let tag = SerdeAttributes::try_from_attributes(attrs)
-        .unwrap_or_default()
-        .tag?;
+    let serde_attrs = SerdeAttributes::try_from_attributes(attrs).unwrap_or_default();
+    let tag = serde_attrs.tag?;
     let attributes = super::parse_attributes(attrs);
+    let schema_attrs = SchemaAttributes::try_from_attributes(attrs).unwrap_or_default();
 
     let variants = data.variants.iter().map(|v| {
-        let ident = &v.ident.to_string();
+        // serde writes the renamed variant name into the tag.
+        let ident = super::variant_name(v);
         let attributes = super::parse_attributes(&v.attrs);
+        let merge_all_of = super::merge_all_of();
+        // Keys of flattened fields are not all known as properties, so such variants
+        // stay open to other keys.
+        let has_flatten = v.fields.iter().any(super::is_flattened);
+        let closed = !schema_attrs.allow_unknown_fields && !has_flatten;
         let add_field_properties = match &v.fields {
-            Fields::Named(fields) => super::field_props(fields),
-            Fields::Unit => quote! { (Vec::new(), serde_json::Map::new()) },
+            Fields::Named(fields) => {
+                // A variant's own `rename_all` takes precedence over the enum's `rename_all_fields`.
+                let naming = FieldNaming {
+                    prefix: schema_attrs.rename_prefix(),
+                    rename_all: rename_all(&v.attrs).or(serde_attrs.rename_all_fields),
+                };
+                super::field_props(fields, &naming)
+            }
+            Fields::Unit => quote! { (json_schema_derive::__private::Vec::new(), serde_json::Map::new(), None, json_schema_derive::__private::Vec::new()) },
             Fields::Unnamed(_) => Error::new_spanned(&v.ident, "Unnamed emum not with tags")
                 .to_compile_error(),
         };
@@ -106,23 +281,64 @@ pub(crate) fn serde_data_enum<'a>(
             let mut map = serde_json::Map::new();
             map.insert("type".into(), "object".into());
 
-            let (mut required, mut properties) = #add_field_properties;
+            let (mut required, mut properties, additional_properties, all_of) = #add_field_properties;
 
             properties.insert(#tag.into(), serde_json::json!({ "type": "string", "const": #ident }));
             required.push(#tag.into());
 
             map.insert("properties".into(), serde_json::Value::Object(properties));
-            map.insert("required".into(), serde_json::Value::Array(required));
+            if !required.is_empty() || !config.omit_empty_required {
+                map.insert("required".into(), serde_json::Value::Array(required));
+            }
+            if let Some(additional_properties) = additional_properties {
+                map.insert("additionalProperties".into(), additional_properties);
+            } else if #closed {
+                map.insert("additionalProperties".into(), serde_json::Value::Bool(false));
+            }
 
             #( map.insert(#attributes); )*
+            #merge_all_of
             serde_json::Value::Object(map)
         }}
     });
 
+    let hoist_required = match schema_attrs.hoist_required {
+        true => quote! {
+            let mut shared: Option<json_schema_derive::__private::Vec<serde_json::Value>> = None;
+            for variant in &one_of {
+                let required = variant
+                    .get("required")
+                    .and_then(serde_json::Value::as_array)
+                    .cloned()
+                    .unwrap_or_default();
+                shared = Some(match shared {
+                    Some(shared) => shared.into_iter().filter(|key| required.contains(key)).collect(),
+                    None => required,
+                });
+            }
+            let shared = shared.unwrap_or_default();
+            if !shared.is_empty() {
+                for variant in &mut one_of {
+                    if let Some(serde_json::Value::Array(required)) = variant.get_mut("required") {
+                        required.retain(|key| !shared.contains(key));
+                        if required.is_empty() && config.omit_empty_required {
+                            if let serde_json::Value::Object(variant) = variant {
+                                variant.remove("required");
+                            }
+                        }
+                    }
+                }
+                map.insert("required".into(), serde_json::Value::Array(shared));
+            }
+        },
+        false => quote! {},
+    };
+
     Some(quote! {{
         let mut map = serde_json::Map::new();
-        let mut one_of: Vec<serde_json::Value> = Vec::new();
+        let mut one_of: json_schema_derive::__private::Vec<serde_json::Value> = json_schema_derive::__private::Vec::new();
         #( one_of.push(#variants); )*
+        #hoist_required
         map.insert("oneOf".into(), serde_json::Value::Array(one_of));
         #( map.insert(#attributes); )*
         serde_json::Value::Object(map)
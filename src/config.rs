@@ -0,0 +1,183 @@
+use alloc::borrow::ToOwned;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+
+use serde_json::{Map, Value};
+
+/// Named schemas collected while generating a schema, keyed by type name.
+pub type Definitions = Map<String, Value>;
+
+/// JSON Schema dialect targeted by the generated schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Draft {
+    /// Draft 7 (`http://json-schema.org/draft-07/schema#`).
+    Draft07,
+    /// Draft 2019-09 (`https://json-schema.org/draft/2019-09/schema`).
+    Draft201909,
+    /// Draft 2020-12 (`https://json-schema.org/draft/2020-12/schema`).
+    #[default]
+    Draft202012,
+}
+
+impl Draft {
+    /// The meta-schema URI identifying the draft, as used by `$schema`.
+    pub const fn uri(&self) -> &'static str {
+        match self {
+            Draft::Draft07 => "http://json-schema.org/draft-07/schema#",
+            Draft::Draft201909 => "https://json-schema.org/draft/2019-09/schema",
+            Draft::Draft202012 => "https://json-schema.org/draft/2020-12/schema",
+        }
+    }
+}
+
+/// How `Option<T>` fields express that they may be `null`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Nullable {
+    /// Optional fields are only left out of `required`.
+    #[default]
+    Omit,
+    /// Optional fields also get the OpenAPI 3.0 `nullable: true` keyword.
+    Keyword,
+    /// Every `Option<T>` accepts `null` through `anyOf: [T, { "type": "null" }]`,
+    /// including nested ones such as the items of `Vec<Option<T>>`.
+    AnyOf,
+}
+
+/// Options controlling how a schema is generated.
+///
+/// ```rust
+/// use json_schema_derive::{JsonSchema, JsonSchemaConfig};
+///
+/// let config = JsonSchemaConfig {
+///     inline: false,
+///     include_schema: true,
+///     ..Default::default()
+/// };
+/// let schema = User::json_schema_with(&config);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonSchemaConfig {
+    /// Draft the schema targets.
+    pub draft: Draft,
+    /// Inline nested types, or place them in `$defs` and reference them with `$ref`.
+    pub inline: bool,
+    /// Emit the `$schema` keyword on the root schema.
+    pub include_schema: bool,
+    /// How optional fields allow `null`.
+    pub nullable: Nullable,
+    /// Emit `"type": "integer"` for integer types instead of `"number"`, so that
+    /// fractional values are rejected.
+    pub integer_type: bool,
+    /// Add the `minimum` and `maximum` of fixed-width integer types, and the smallest and
+    /// largest discriminant of `repr = "int"` enums.
+    pub infer_integer_bounds: bool,
+    /// Leave out the `required` keyword of objects without required properties.
+    pub omit_empty_required: bool,
+}
+
+impl Default for JsonSchemaConfig {
+    fn default() -> Self {
+        Self {
+            draft: Draft::default(),
+            inline: true,
+            include_schema: false,
+            nullable: Nullable::default(),
+            integer_type: false,
+            infer_integer_bounds: false,
+            omit_empty_required: false,
+        }
+    }
+}
+
+impl JsonSchemaConfig {
+    /// Generate the schema of the named type `name`.
+    ///
+    /// When inlining, this returns the schema built by `schema`. Otherwise the schema is
+    /// registered in `defs` under `name` (once) and a `$ref` to it is returned.
+    pub fn define(
+        &self,
+        defs: &mut Definitions,
+        name: &str,
+        schema: impl FnOnce(&mut Definitions) -> Value,
+    ) -> Value {
+        if self.inline {
+            return schema(defs);
+        }
+        if !defs.contains_key(name) {
+            // Reserve the name first so recursive types resolve to the same `$ref`.
+            defs.insert(name.into(), Value::Bool(true));
+            let schema = schema(defs);
+            defs.insert(name.into(), schema);
+        }
+        serde_json::json!({ "$ref": format!("#/$defs/{name}") })
+    }
+
+    /// Mark the schema of an `Option<T>` field as accepting `null`.
+    pub fn nullable(&self, schema: Value) -> Value {
+        match self.nullable {
+            // `Option<T>` itself already allows `null` in `AnyOf` mode.
+            Nullable::Omit | Nullable::AnyOf => schema,
+            Nullable::Keyword => {
+                let Value::Object(mut map) = schema else {
+                    return schema;
+                };
+                // Keywords next to `$ref` are ignored by OpenAPI 3.0, so the reference is
+                // wrapped in `allOf`.
+                if map.contains_key("$ref") {
+                    let reference = Value::Object(core::mem::take(&mut map));
+                    map.insert("allOf".into(), Value::Array(vec![reference]));
+                }
+                map.insert("nullable".into(), Value::Bool(true));
+                Value::Object(map)
+            }
+        }
+    }
+
+    /// Turn the schema of a type into a root schema document identified by `id`.
+    pub(crate) fn root(&self, schema: Value, mut defs: Definitions, id: Option<&str>) -> Value {
+        // A root that is only a reference to its own definition is replaced by that
+        // definition, unless other definitions still point at it.
+        let reference = schema
+            .as_object()
+            .filter(|map| map.len() == 1)
+            .and_then(|map| map.get("$ref"))
+            .and_then(Value::as_str)
+            .map(str::to_owned);
+        let schema = match reference {
+            Some(reference) => match reference.strip_prefix("#/$defs/") {
+                Some(name) if !defs.values().any(|def| references(def, &reference)) => {
+                    defs.remove(name).unwrap_or(schema)
+                }
+                _ => schema,
+            },
+            None => schema,
+        };
+
+        let Value::Object(schema) = schema else {
+            return schema;
+        };
+        let mut map = Map::new();
+        if self.include_schema {
+            map.insert("$schema".into(), self.draft.uri().into());
+        }
+        if let Some(id) = id {
+            map.insert("$id".into(), id.into());
+        }
+        map.extend(schema);
+        if !defs.is_empty() {
+            map.insert("$defs".into(), Value::Object(defs));
+        }
+        Value::Object(map)
+    }
+}
+
+fn references(value: &Value, reference: &str) -> bool {
+    match value {
+        Value::Object(map) => map.iter().any(|(key, value)| {
+            (key == "$ref" && value.as_str() == Some(reference)) || references(value, reference)
+        }),
+        Value::Array(items) => items.iter().any(|value| references(value, reference)),
+        _ => false,
+    }
+}
@@ -23,14 +23,39 @@
 //! # Features
 //!
 //! - `serde-compat`: Enables compatibility with serde attributes for schema generation
+//! - `validate`: Adds [`JsonSchema::validate`], checking instances against the schema with `jsonschema`
+//! - `decimal`: Implements [`JsonSchema`] for `rust_decimal::Decimal`, serialized as a string
+//! - `decimal-float`: Enables `decimal` with `rust_decimal`'s `serde-float`, serializing decimals as numbers
+//!
+//! # Configuration
+//!
+//! [`JsonSchema::json_schema_with`] generates the schema using a [`JsonSchemaConfig`],
+//! for example to place nested types in `$defs` instead of inlining them:
+//!
+//! ```rust
+//! use json_schema_derive::{JsonSchema, JsonSchemaConfig};
+//!
+//! let config = JsonSchemaConfig { inline: false, ..Default::default() };
+//! let schema = User::json_schema_with(&config);
+//! ```
+//!
+//! [`JsonSchema::schema`] returns the schema as a typed [`Schema`] instead of a
+//! `serde_json::Value`.
+//!
 //! # Serde Compatibility
 //!
 //! When the `serde-compat` feature is enabled, the following `serde` attributes are supported:
 //!
 //! - `#[serde(skip)]` – Omits the field from the schema  
 //! - `#[serde(rename = "new_name")]` – Renames the field in the schema  
-//! - `#[serde(flatten)]` – Inlines nested struct fields  
-//! - `#[serde(tag = "...")]` – Supports internally tagged enums
+//! - `#[serde(alias = "...")]` – Records the other accepted names in an `x-aliases` annotation  
+//! - `#[serde(rename_all = "...")]` – Renames the fields of a struct or struct variant  
+//! - `#[serde(rename_all_fields = "...")]` – Renames the fields of every struct variant of a tagged enum  
+//! - `#[serde(with = "...")]` – Accepts any value and warns, unless the field has `#[json_schema(with = "...")]`, `schema` or `bytes`  
+//! - `#[serde(deny_unknown_fields)]` – Rejects other keys, with `unevaluatedProperties` when fields are flattened  
+//! - `#[serde(flatten)]` – Inlines nested struct fields, adds the variants of enums to `allOf`, or collects extra keys into a map  
+//! - `#[serde(tag = "...")]` – Supports internally tagged enums, whose variants reject unknown
+//!   keys unless the enum has `#[json_schema(allow_unknown_fields)]`
 //!
 //! ```rust
 //! #[derive(JsonSchema)]
@@ -41,20 +66,219 @@
 //! }
 //! ```
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::borrow::{Cow, ToOwned};
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
 use core::str;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "std")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "std")]
+use std::time::SystemTime;
 
+// Lets the paths emitted by the derive macro resolve inside this crate.
+extern crate self as json_schema_derive;
+
+pub use config::{Definitions, Draft, JsonSchemaConfig, Nullable};
 pub use json_schema_derive_macro::JsonSchema;
+pub use schema::Schema;
+#[doc(hidden)]
+pub use validate::ValidatorCell;
+
+mod config;
+mod schema;
+mod validate;
+
+// Items of `alloc` used by the code generated by the derive macro, which must also build
+// in `no_std` crates without the standard prelude.
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::borrow::Cow;
+    pub use alloc::format;
+    pub use alloc::string::String;
+    pub use alloc::vec;
+    pub use alloc::vec::Vec;
+
+    /// The `$defs` name of an instantiation of a generic type, such as
+    /// `Either_u32_String` for `Either<u32, String>`.
+    pub fn generic_name(name: &str, args: &[Cow<'static, str>]) -> Cow<'static, str> {
+        let mut generic = String::from(name);
+        for arg in args {
+            generic.push('_');
+            generic.push_str(&short_name(arg));
+        }
+        Cow::Owned(generic)
+    }
+
+    /// A type name without module paths, with every other punctuation replaced by `_`.
+    fn short_name(name: &str) -> String {
+        let mut short = String::new();
+        let mut segment = String::new();
+        let mut chars = name.chars().peekable();
+        while let Some(ch) = chars.next() {
+            match ch {
+                // A path separator drops the module before it.
+                ':' => {
+                    chars.next_if_eq(&':');
+                    segment.clear();
+                }
+                ch if ch.is_alphanumeric() || ch == '_' => segment.push(ch),
+                _ => {
+                    short.push_str(&segment);
+                    segment.clear();
+                    if !short.ends_with('_') {
+                        short.push('_');
+                    }
+                }
+            }
+        }
+        short.push_str(&segment);
+        short.trim_matches('_').into()
+    }
+}
 // mod expanded;
 
 /// Trait for generating JSON Schema from a type.
 ///
 /// This trait is automatically implemented for types that derive `JsonSchema`.
 /// It provides a method to generate a JSON Schema representation of the type.
+///
+/// Manual implementations provide [`JsonSchema::json_schema`], from which the typed
+/// [`JsonSchema::schema`] is derived. A schema built as a typed [`Schema`] is returned
+/// from `json_schema` with `.into()`.
 pub trait JsonSchema {
+    /// JSON Schema draft the type targets, set on derived types with
+    /// `#[json_schema(draft = "07")]`.
+    ///
+    /// [`JsonSchema::json_schema`] and [`bundle`] generate the schema for this draft.
+    const DRAFT: Draft = Draft::Draft202012;
+
+    /// URI of the JSON Schema draft the type targets.
+    const SCHEMA_VERSION: &'static str = Self::DRAFT.uri();
+
     /// Generate a JSON Schema representation of the type.
     ///
     /// Returns a `serde_json::Value` containing the JSON Schema.
     fn json_schema() -> serde_json::Value;
+
+    /// Generate a JSON Schema representation of the type using `config`.
+    ///
+    /// The default implementation delegates to [`JsonSchema::json_schema`] through
+    /// [`JsonSchema::json_schema_in`] and applies the root-level options of `config`.
+    fn json_schema_with(config: &JsonSchemaConfig) -> serde_json::Value {
+        let mut defs = Definitions::new();
+        let schema = Self::json_schema_in(config, &mut defs);
+        config.root(schema, defs, Self::json_schema_id())
+    }
+
+    /// The `$id` of the root schema of the type, set with `#[json_schema(id = "...")]`.
+    fn json_schema_id() -> Option<&'static str> {
+        None
+    }
+
+    /// Generate the schema of the type as it appears inside another schema.
+    ///
+    /// Named types register their schema in `defs` and return a `$ref` to it when
+    /// `config.inline` is `false`.
+    fn json_schema_in(config: &JsonSchemaConfig, defs: &mut Definitions) -> serde_json::Value {
+        let _ = (config, defs);
+        Self::json_schema()
+    }
+
+    /// Generate the JSON Schema of the type as a typed [`Schema`], split from the schema
+    /// of [`JsonSchema::json_schema`].
+    fn schema() -> Schema {
+        Schema::from(Self::json_schema())
+    }
+
+    /// Name under which the schema of the type is registered in `$defs`.
+    ///
+    /// Derived types use their identifier, followed by the names of their type arguments
+    /// for generic types; other types default to their Rust type name.
+    fn json_schema_name() -> Cow<'static, str> {
+        Cow::Borrowed(core::any::type_name::<Self>())
+    }
+
+    /// A `$ref` to the schema of the type in `$defs`, for composing schemas by hand.
+    fn json_schema_ref() -> serde_json::Value {
+        serde_json::json!({ "$ref": format!("#/$defs/{}", Self::json_schema_name()) })
+    }
+
+    /// Generate the named schema of the type for assembly by external bundling tools.
+    ///
+    /// Returns the definition name with the bare schema, which carries no root-only
+    /// keywords such as `$schema`, `$id` or `$defs`. The named types its `$ref`s point to
+    /// are given by [`JsonSchema::json_schema_fragment_defs`].
+    fn json_schema_fragment() -> (String, serde_json::Value) {
+        let (schema, _) = fragment::<Self>();
+        (Self::json_schema_name().into(), schema)
+    }
+
+    /// The definitions of the named types referenced by [`JsonSchema::json_schema_fragment`].
+    fn json_schema_fragment_defs() -> Definitions {
+        let (_, defs) = fragment::<Self>();
+        defs
+    }
+
+    /// Validate `value` against the JSON Schema of the type.
+    ///
+    /// Returns the message of every validation error on failure.
+    /// Derived types without type parameters compile their schema once and reuse it.
+    #[cfg(feature = "validate")]
+    fn validate(value: &serde_json::Value) -> Result<(), Vec<String>> {
+        // `$schema` makes the validator follow the draft the schema targets.
+        let config = JsonSchemaConfig {
+            draft: Self::DRAFT,
+            include_schema: true,
+            ..Default::default()
+        };
+        let compile = || {
+            jsonschema::validator_for(&Self::json_schema_with(&config))
+                .map_err(|err| vec![format!("invalid schema: {err}")])
+        };
+        let errors: Vec<String> = match Self::validator_cell() {
+            Some(cell) => cell
+                .get_or_try_init(compile)?
+                .iter_errors(value)
+                .map(|err| err.to_string())
+                .collect(),
+            None => compile()?
+                .iter_errors(value)
+                .map(|err| err.to_string())
+                .collect(),
+        };
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors),
+        }
+    }
+
+    /// Cache for the compiled validator of the type, if it has one.
+    #[doc(hidden)]
+    fn validator_cell() -> Option<&'static ValidatorCell> {
+        None
+    }
+
+    /// Generate the JSON Schema of the type serialized as a compact string.
+    fn json_schema_string() -> String {
+        serde_json::to_string(&Self::json_schema()).unwrap()
+    }
+
+    /// Generate the JSON Schema of the type serialized as a pretty-printed string.
+    fn json_schema_string_pretty() -> String {
+        serde_json::to_string_pretty(&Self::json_schema()).unwrap()
+    }
 }
 
 macro_rules! impl_json_schema {
@@ -69,9 +293,43 @@ macro_rules! impl_json_schema {
     };
 }
 
-impl_json_schema!("number", u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+macro_rules! impl_json_schema_integer {
+    ($($t:ty),*) => {
+        $(
+            impl JsonSchema for $t {
+                fn json_schema() -> serde_json::Value {
+                    Self::json_schema_with(&JsonSchemaConfig::default())
+                }
+
+                fn json_schema_in(config: &JsonSchemaConfig, _defs: &mut Definitions) -> serde_json::Value {
+                    let mut map = serde_json::Map::new();
+                    let ty = match config.integer_type {
+                        true => "integer",
+                        false => "number",
+                    };
+                    map.insert("type".into(), ty.into());
+                    // Bounds beyond the 64-bit range of JSON numbers are left out.
+                    if config.infer_integer_bounds {
+                        if let Ok(min) = i64::try_from(<$t>::MIN as i128) {
+                            map.insert("minimum".into(), min.into());
+                        }
+                        if let Ok(max) = u64::try_from(<$t>::MAX as u128) {
+                            map.insert("maximum".into(), max.into());
+                        }
+                    }
+                    serde_json::Value::Object(map)
+                }
+            }
+        )*
+    };
+}
+
+impl_json_schema_integer!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+impl_json_schema!("number", f32, f64);
 impl_json_schema!("boolean", bool);
-impl_json_schema!("string", String, &str);
+impl_json_schema!("string", String, str);
+#[cfg(feature = "std")]
+impl_json_schema!("string", PathBuf, Path);
 
 impl JsonSchema for () {
     fn json_schema() -> serde_json::Value {
@@ -79,15 +337,166 @@ impl JsonSchema for () {
     }
 }
 
+// Serialized as unit by serde. Derived structs leave `PhantomData` fields out entirely.
+impl<T: ?Sized> JsonSchema for PhantomData<T> {
+    fn json_schema() -> serde_json::Value {
+        serde_json::json!({ "type": "null" })
+    }
+}
+
+// The empty schema accepts any value, and unlike `true` can still carry keywords.
+impl JsonSchema for serde_json::Value {
+    fn json_schema() -> serde_json::Value {
+        serde_json::json!({})
+    }
+}
+
+impl JsonSchema for serde_json::Map<String, serde_json::Value> {
+    fn json_schema() -> serde_json::Value {
+        serde_json::json!({ "type": "object" })
+    }
+}
+
+// Serialized by serde as whole seconds and the remaining nanoseconds.
+impl JsonSchema for Duration {
+    fn json_schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "secs": { "type": "integer" },
+                "nanos": { "type": "integer" }
+            },
+            "required": ["secs", "nanos"]
+        })
+    }
+}
+
+// Serialized by serde as the duration since the Unix epoch.
+#[cfg(feature = "std")]
+impl JsonSchema for SystemTime {
+    fn json_schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "secs_since_epoch": { "type": "integer" },
+                "nanos_since_epoch": { "type": "integer" }
+            },
+            "required": ["secs_since_epoch", "nanos_since_epoch"]
+        })
+    }
+}
+
+// Serialized by serde as a decimal string, or as a number with `serde-float`.
+#[cfg(feature = "decimal")]
+impl JsonSchema for rust_decimal::Decimal {
+    fn json_schema() -> serde_json::Value {
+        match cfg!(feature = "decimal-float") {
+            true => serde_json::json!({ "type": "number" }),
+            false => serde_json::json!({ "type": "string", "pattern": "^-?\\d+(\\.\\d+)?$" }),
+        }
+    }
+}
+
 impl<T: JsonSchema> JsonSchema for Vec<T> {
     fn json_schema() -> serde_json::Value {
-        serde_json::json!({ "type": "array", "items": T::json_schema() })
+        Self::json_schema_with(&JsonSchemaConfig::default())
+    }
+
+    fn json_schema_in(config: &JsonSchemaConfig, defs: &mut Definitions) -> serde_json::Value {
+        serde_json::json!({ "type": "array", "items": T::json_schema_in(config, defs) })
+    }
+}
+
+impl<T: JsonSchema> JsonSchema for [T] {
+    fn json_schema() -> serde_json::Value {
+        Self::json_schema_with(&JsonSchemaConfig::default())
+    }
+
+    fn json_schema_in(config: &JsonSchemaConfig, defs: &mut Definitions) -> serde_json::Value {
+        serde_json::json!({ "type": "array", "items": T::json_schema_in(config, defs) })
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: JsonSchema> JsonSchema for HashSet<T> {
+    fn json_schema() -> serde_json::Value {
+        Self::json_schema_with(&JsonSchemaConfig::default())
+    }
+
+    fn json_schema_in(config: &JsonSchemaConfig, defs: &mut Definitions) -> serde_json::Value {
+        serde_json::json!({ "type": "array", "items": T::json_schema_in(config, defs), "uniqueItems": true })
+    }
+}
+
+impl<T: JsonSchema> JsonSchema for BTreeSet<T> {
+    fn json_schema() -> serde_json::Value {
+        Self::json_schema_with(&JsonSchemaConfig::default())
+    }
+
+    fn json_schema_in(config: &JsonSchemaConfig, defs: &mut Definitions) -> serde_json::Value {
+        serde_json::json!({ "type": "array", "items": T::json_schema_in(config, defs), "uniqueItems": true })
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: JsonSchema, V: JsonSchema> JsonSchema for HashMap<K, V> {
+    fn json_schema() -> serde_json::Value {
+        Self::json_schema_with(&JsonSchemaConfig::default())
+    }
+
+    fn json_schema_in(config: &JsonSchemaConfig, defs: &mut Definitions) -> serde_json::Value {
+        map_schema::<K, V>(config, defs)
+    }
+}
+
+impl<K: JsonSchema, V: JsonSchema> JsonSchema for BTreeMap<K, V> {
+    fn json_schema() -> serde_json::Value {
+        Self::json_schema_with(&JsonSchemaConfig::default())
     }
+
+    fn json_schema_in(config: &JsonSchemaConfig, defs: &mut Definitions) -> serde_json::Value {
+        map_schema::<K, V>(config, defs)
+    }
+}
+
+/// Schema of a map serialized as an object with keys of type `K` and values of type `V`.
+///
+/// Keys whose schema is a string `enum`, such as unit enums, restrict the property names
+/// to its values.
+fn map_schema<K: JsonSchema, V: JsonSchema>(
+    config: &JsonSchemaConfig,
+    defs: &mut Definitions,
+) -> serde_json::Value {
+    let mut map = serde_json::Map::new();
+    map.insert("type".into(), "object".into());
+    // The key schema is only inspected, so it is generated inline without touching `defs`.
+    let key_config = JsonSchemaConfig {
+        inline: true,
+        ..config.clone()
+    };
+    let key_schema = K::json_schema_in(&key_config, &mut Definitions::new());
+    if let Some(values) = key_schema.get("enum").and_then(serde_json::Value::as_array) {
+        if values.iter().all(serde_json::Value::is_string) {
+            map.insert(
+                "propertyNames".into(),
+                serde_json::json!({ "enum": values }),
+            );
+        }
+    }
+    map.insert(
+        "additionalProperties".into(),
+        V::json_schema_in(config, defs),
+    );
+    serde_json::Value::Object(map)
 }
 
 impl<T: JsonSchema, const N: usize> JsonSchema for [T; N] {
     fn json_schema() -> serde_json::Value {
-        serde_json::json!({ "type": "array", "items": T::json_schema(), "maxItems": N, "minItems": N })
+        Self::json_schema_with(&JsonSchemaConfig::default())
+    }
+
+    fn json_schema_in(config: &JsonSchemaConfig, defs: &mut Definitions) -> serde_json::Value {
+        serde_json::json!({ "type": "array", "items": T::json_schema_in(config, defs), "maxItems": N, "minItems": N })
     }
 }
 
@@ -95,24 +504,159 @@ impl<T: JsonSchema> JsonSchema for Option<T> {
     fn json_schema() -> serde_json::Value {
         T::json_schema()
     }
+
+    fn json_schema_in(config: &JsonSchemaConfig, defs: &mut Definitions) -> serde_json::Value {
+        let schema = T::json_schema_in(config, defs);
+        match config.nullable {
+            Nullable::AnyOf => serde_json::json!({ "anyOf": [schema, { "type": "null" }] }),
+            _ => schema,
+        }
+    }
+}
+
+impl<T: JsonSchema + ?Sized> JsonSchema for &T {
+    fn json_schema() -> serde_json::Value {
+        T::json_schema()
+    }
+
+    fn json_schema_in(config: &JsonSchemaConfig, defs: &mut Definitions) -> serde_json::Value {
+        T::json_schema_in(config, defs)
+    }
 }
 
-impl<T: JsonSchema> JsonSchema for &Option<T> {
+impl<T: JsonSchema + ToOwned + ?Sized> JsonSchema for Cow<'_, T> {
     fn json_schema() -> serde_json::Value {
         T::json_schema()
     }
+
+    fn json_schema_in(config: &JsonSchemaConfig, defs: &mut Definitions) -> serde_json::Value {
+        T::json_schema_in(config, defs)
+    }
 }
 
 impl<T: JsonSchema> JsonSchema for Box<T> {
     fn json_schema() -> serde_json::Value {
         T::json_schema()
     }
+
+    fn json_schema_in(config: &JsonSchemaConfig, defs: &mut Definitions) -> serde_json::Value {
+        T::json_schema_in(config, defs)
+    }
+}
+
+// Serialized by serde as an object holding only `Ok` or `Err`.
+impl<T: JsonSchema, E: JsonSchema> JsonSchema for Result<T, E> {
+    fn json_schema() -> serde_json::Value {
+        Self::json_schema_with(&JsonSchemaConfig::default())
+    }
+
+    fn json_schema_in(config: &JsonSchemaConfig, defs: &mut Definitions) -> serde_json::Value {
+        let variant = |name: &str, schema: serde_json::Value| {
+            serde_json::json!({
+                "type": "object",
+                "properties": { name: schema },
+                "required": [name],
+                "additionalProperties": false
+            })
+        };
+        let ok = variant("Ok", T::json_schema_in(config, defs));
+        let err = variant("Err", E::json_schema_in(config, defs));
+        serde_json::json!({ "oneOf": [ok, err] })
+    }
+}
+
+macro_rules! impl_json_schema_tuple {
+    ($(($($t:ident),+)),*) => {
+        $(
+            impl<$($t: JsonSchema),+> JsonSchema for ($($t,)+) {
+                fn json_schema() -> serde_json::Value {
+                    Self::json_schema_with(&JsonSchemaConfig::default())
+                }
+
+                fn json_schema_in(config: &JsonSchemaConfig, defs: &mut Definitions) -> serde_json::Value {
+                    let items = vec![$($t::json_schema_in(config, defs)),+];
+                    let count = items.len();
+                    match config.draft {
+                        Draft::Draft202012 => serde_json::json!({
+                            "type": "array",
+                            "prefixItems": items,
+                            "minItems": count,
+                            "maxItems": count,
+                            "unevaluatedItems": false
+                        }),
+                        _ => serde_json::json!({
+                            "type": "array",
+                            "items": items,
+                            "minItems": count,
+                            "maxItems": count,
+                            "additionalItems": false
+                        }),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_json_schema_tuple!(
+    (A),
+    (A, B),
+    (A, B, C),
+    (A, B, C, D),
+    (A, B, C, D, E),
+    (A, B, C, D, E, F),
+    (A, B, C, D, E, F, G),
+    (A, B, C, D, E, F, G, H),
+    (A, B, C, D, E, F, G, H, I),
+    (A, B, C, D, E, F, G, H, I, J),
+    (A, B, C, D, E, F, G, H, I, J, K),
+    (A, B, C, D, E, F, G, H, I, J, K, L)
+);
+
+/// Combine schemas with `allOf`: an instance must be valid against all of them.
+pub fn all_of(schemas: Vec<serde_json::Value>) -> serde_json::Value {
+    serde_json::json!({ "allOf": schemas })
+}
+
+/// Combine schemas with `anyOf`: an instance must be valid against at least one of them.
+pub fn any_of(schemas: Vec<serde_json::Value>) -> serde_json::Value {
+    serde_json::json!({ "anyOf": schemas })
+}
+
+/// Combine schemas with `oneOf`: an instance must be valid against exactly one of them.
+pub fn one_of(schemas: Vec<serde_json::Value>) -> serde_json::Value {
+    serde_json::json!({ "oneOf": schemas })
+}
+
+/// The schema of `T` as it appears inside another schema, with the definitions it uses.
+fn fragment<T: JsonSchema + ?Sized>() -> (serde_json::Value, Definitions) {
+    let mut defs = Definitions::new();
+    let config = JsonSchemaConfig {
+        draft: T::DRAFT,
+        ..Default::default()
+    };
+    let schema = T::json_schema_in(&config, &mut defs);
+    (schema, defs)
+}
+
+/// Generate a complete schema document for `T`.
+///
+/// The document targets the draft of `T` and carries `$schema`, the `$id` of `T` if it
+/// has one, and every named type referenced by the schema in `$defs`.
+pub fn bundle<T: JsonSchema>() -> serde_json::Value {
+    let config = JsonSchemaConfig {
+        draft: T::DRAFT,
+        inline: false,
+        include_schema: true,
+        ..Default::default()
+    };
+    T::json_schema_with(&config)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde::Serialize;
+    use serde::{Deserialize, Serialize};
     use serde_json::json;
 
     pub fn valid<T: JsonSchema + Serialize>(instance: &T) -> bool {
@@ -144,6 +688,44 @@ mod tests {
         assert!(valid::<[u32; 3]>(&[1, 2, 3]));
     }
 
+    #[test]
+    fn test_all_of() {
+        let schema = all_of(vec![
+            u32::json_schema(),
+            json!({ "minimum": 10 }),
+            json!({ "maximum": 20 }),
+        ]);
+        assert_eq!(
+            schema,
+            json!({ "allOf": [{ "type": "number" }, { "minimum": 10 }, { "maximum": 20 }] })
+        );
+        assert!(jsonschema::is_valid(&schema, &json!(15)));
+        assert!(!jsonschema::is_valid(&schema, &json!(25)));
+    }
+
+    #[test]
+    fn test_any_of() {
+        let schema = any_of(vec![u32::json_schema(), String::json_schema()]);
+        assert_eq!(
+            schema,
+            json!({ "anyOf": [{ "type": "number" }, { "type": "string" }] })
+        );
+        assert!(jsonschema::is_valid(&schema, &json!(1)));
+        assert!(jsonschema::is_valid(&schema, &json!("test")));
+        assert!(!jsonschema::is_valid(&schema, &json!(true)));
+    }
+
+    #[test]
+    fn test_one_of() {
+        let schema = one_of(vec![json!({ "minimum": 10 }), json!({ "maximum": 20 })]);
+        assert_eq!(
+            schema,
+            json!({ "oneOf": [{ "minimum": 10 }, { "maximum": 20 }] })
+        );
+        assert!(jsonschema::is_valid(&schema, &json!(5)));
+        assert!(!jsonschema::is_valid(&schema, &json!(15)));
+    }
+
     #[derive(JsonSchema, Serialize)]
     #[json_schema(comment = "Test comment")]
     #[allow(dead_code)]
@@ -155,11 +737,65 @@ mod tests {
         scores: Vec<i32>,
     }
 
+    #[derive(JsonSchema)]
+    #[json_schema(draft = "07")]
+    #[allow(dead_code)]
+    struct TestStructDraft07 {
+        pair: (String, u32),
+    }
+
+    #[test]
+    fn test_schema_version() {
+        assert_eq!(
+            TestStruct::SCHEMA_VERSION,
+            "https://json-schema.org/draft/2020-12/schema"
+        );
+        assert_eq!(TestStructDraft07::DRAFT, Draft::Draft07);
+        assert_eq!(TestStructDraft07::SCHEMA_VERSION, Draft::Draft07.uri());
+        assert_eq!(u32::SCHEMA_VERSION, Draft::Draft202012.uri());
+
+        let schema = TestStructDraft07::json_schema();
+        assert_eq!(
+            schema["properties"]["pair"]["items"],
+            json!([{ "type": "string" }, { "type": "number" }])
+        );
+        assert!(schema["properties"]["pair"].get("prefixItems").is_none());
+        let bundled = bundle::<TestStructDraft07>();
+        assert_eq!(bundled["$schema"], json!(Draft::Draft07.uri()));
+        assert_eq!(bundled["properties"], schema["properties"]);
+        let (_, fragment) = TestStructDraft07::json_schema_fragment();
+        assert_eq!(fragment, schema);
+        #[cfg(feature = "validate")]
+        {
+            assert_eq!(
+                TestStructDraft07::validate(&json!({ "pair": ["a", 1] })),
+                Ok(())
+            );
+            assert!(TestStructDraft07::validate(&json!({ "pair": [1, "a"] })).is_err());
+        }
+    }
+
+    #[derive(JsonSchema)]
+    #[json_schema(title = "Titled")]
+    #[allow(dead_code)]
+    struct TestStructTitled {
+        name: String,
+    }
+
+    #[test]
+    fn test_struct_title() {
+        let schema = TestStruct::json_schema();
+        assert_eq!(schema["title"], json!("TestStruct"));
+        let schema = TestStructTitled::json_schema();
+        assert_eq!(schema["title"], json!("Titled"));
+    }
+
     #[test]
     fn test_struct_schema() {
         let schema = TestStruct::json_schema();
         let expected = json!({
             "type": "object",
+            "title": "TestStruct",
             "properties": {
                 "name": {
                     "type": "string",
@@ -201,9 +837,11 @@ mod tests {
         let schema = NestedStruct::json_schema();
         let expected = json!({
             "type": "object",
+            "title": "NestedStruct",
             "properties": {
                 "inner": {
                     "type": "object",
+                    "title": "TestStruct",
                     "properties": {
                         "name": {
                             "type": "string",
@@ -234,190 +872,3060 @@ mod tests {
         assert_eq!(schema, expected);
     }
 
-    #[derive(JsonSchema, Serialize)]
-    #[json_schema(comment = "Test comment")]
+    #[derive(JsonSchema)]
     #[allow(dead_code)]
-    struct TestStructUnnamed(String);
+    struct TestStructFlatten {
+        id: u32,
+        #[json_schema(flatten)]
+        inner: TestStruct,
+    }
 
     #[test]
-    fn test_struct_unnamed() {
-        let schema = TestStructUnnamed::json_schema();
-        let expected = json!({ "comment": "Test comment", "type": "string" });
-        assert_eq!(schema, expected);
-        assert!(valid(&TestStructUnnamed("test".to_string())));
+    fn test_struct_flatten() {
+        let schema = TestStructFlatten::json_schema();
+        assert_eq!(
+            schema["properties"],
+            json!({
+                "id": { "type": "number" },
+                "name": { "type": "string", "comment": "test field", "minLength": 3 },
+                "age": { "type": "number" },
+                "active": { "type": "boolean" },
+                "scores": { "type": "array", "items": { "type": "number" } }
+            })
+        );
+        assert_eq!(schema["required"], json!(["id", "name", "age", "scores"]));
+        let instance = json!({ "id": 1, "name": "test", "age": 10, "scores": [] });
+        assert!(jsonschema::is_valid(&schema, &instance));
+        assert!(!jsonschema::is_valid(&schema, &json!({ "id": 1 })));
+
+        let config = JsonSchemaConfig {
+            inline: false,
+            ..Default::default()
+        };
+        for flattened in [
+            TestStructFlatten::json_schema_with(&config),
+            bundle::<TestStructFlatten>(),
+        ] {
+            assert_eq!(flattened["properties"], schema["properties"]);
+            assert_eq!(flattened["required"], schema["required"]);
+            assert!(flattened.get("$defs").is_none());
+            assert!(!jsonschema::is_valid(&flattened, &json!({ "id": 1 })));
+        }
     }
 
     #[derive(JsonSchema, Serialize)]
-    #[json_schema(comment = "Test comment")]
     #[allow(dead_code)]
-    struct TestStructUnnamedMultiple(String, u32);
+    struct TestStructGeneric<T>
+    where
+        T: Clone,
+    {
+        value: T,
+        values: Vec<T>,
+    }
 
     #[test]
-    fn test_struct_unnamed_multiple() {
-        let schema = TestStructUnnamedMultiple::json_schema();
-        let expected = json!({
-            "comment": "Test comment",
-            "type": "array",
-            "prefixItems": [{ "type": "string" }, { "type": "number" }],
-            "minItems": 2,
-            "maxItems": 2,
-            "unevaluatedItems": false,
-        });
-        assert_eq!(schema, expected);
-        assert!(valid(&TestStructUnnamedMultiple("test".to_string(), 10)));
+    fn test_struct_generic() {
+        let schema = TestStructGeneric::<String>::json_schema();
+        assert_eq!(
+            schema["properties"],
+            json!({
+                "value": { "type": "string" },
+                "values": { "type": "array", "items": { "type": "string" } }
+            })
+        );
+        assert!(valid(&TestStructGeneric {
+            value: 1u32,
+            values: vec![2, 3],
+        }));
     }
 
     #[derive(JsonSchema, Serialize)]
-    #[json_schema(comment = "Test comment")]
     #[allow(dead_code)]
-    enum EnumUnit {
-        A,
-        B,
-        C,
+    enum Either<L, R> {
+        Left(L),
+        Right(R),
     }
 
     #[test]
-    fn test_enum_unit() {
-        let schema = EnumUnit::json_schema();
+    fn test_enum_generic() {
+        let schema = Either::<u32, String>::json_schema();
+        assert_eq!(
+            schema["oneOf"][0]["properties"]["Left"],
+            json!({ "type": "number" })
+        );
+        assert_eq!(
+            schema["oneOf"][1]["properties"]["Right"],
+            json!({ "type": "string" })
+        );
+        assert!(valid(&Either::<u32, String>::Left(1)));
+        assert!(valid(&Either::<u32, String>::Right("test".to_string())));
+        assert!(!jsonschema::is_valid(&schema, &json!({ "Left": "test" })));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructGenericInstances {
+        first: Either<u32, String>,
+        second: Either<bool, bool>,
+        wrapped: TestStructGeneric<Vec<u32>>,
+    }
+
+    #[test]
+    fn test_generic_definitions() {
+        assert_eq!(
+            Either::<u32, String>::json_schema_name(),
+            "Either_u32_String"
+        );
+        assert_eq!(
+            TestStructGeneric::<Vec<u32>>::json_schema_name(),
+            "TestStructGeneric_Vec_u32"
+        );
+        let instance = TestStructGenericInstances {
+            first: Either::Right("test".to_string()),
+            second: Either::Left(true),
+            wrapped: TestStructGeneric {
+                value: vec![1],
+                values: vec![],
+            },
+        };
+        let instance = serde_json::to_value(&instance).unwrap();
+        let config = JsonSchemaConfig {
+            inline: false,
+            ..Default::default()
+        };
+        for schema in [
+            TestStructGenericInstances::json_schema_with(&config),
+            bundle::<TestStructGenericInstances>(),
+        ] {
+            assert_eq!(
+                schema["properties"]["first"],
+                json!({ "$ref": "#/$defs/Either_u32_String" })
+            );
+            assert_eq!(
+                schema["properties"]["second"],
+                json!({ "$ref": "#/$defs/Either_bool_bool" })
+            );
+            assert_eq!(
+                schema["$defs"]["Either_bool_bool"],
+                Either::<bool, bool>::json_schema()
+            );
+            assert!(jsonschema::is_valid(&schema, &instance));
+        }
+    }
+
+    #[test]
+    fn test_config_inline() {
+        let config = JsonSchemaConfig::default();
+        assert_eq!(
+            NestedStruct::json_schema_with(&config),
+            NestedStruct::json_schema()
+        );
+
+        let config = JsonSchemaConfig {
+            include_schema: true,
+            ..Default::default()
+        };
+        let schema = NestedStruct::json_schema_with(&config);
+        assert_eq!(
+            schema["$schema"],
+            json!("https://json-schema.org/draft/2020-12/schema")
+        );
+        assert_eq!(schema["properties"]["inner"], TestStruct::json_schema());
+        assert!(schema.get("$defs").is_none());
+    }
+
+    #[test]
+    fn test_bundle() {
+        let schema = bundle::<NestedStruct>();
+        assert_eq!(
+            schema["$schema"],
+            json!("https://json-schema.org/draft/2020-12/schema")
+        );
+        assert_eq!(schema["title"], json!("NestedStruct"));
+        assert_eq!(
+            schema["properties"]["inner"],
+            json!({ "$ref": "#/$defs/TestStruct" })
+        );
+        assert_eq!(schema["$defs"]["TestStruct"], TestStruct::json_schema());
+    }
+
+    #[test]
+    fn test_config_defs() {
+        let config = JsonSchemaConfig {
+            inline: false,
+            include_schema: true,
+            draft: Draft::Draft07,
+            ..Default::default()
+        };
+        let schema = NestedStruct::json_schema_with(&config);
+        let expected = json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object",
+            "title": "NestedStruct",
+            "properties": {
+                "inner": { "$ref": "#/$defs/TestStruct" },
+                "tags": {
+                    "type": "array",
+                    "items": {"type": "string"}
+                }
+            },
+            "required": [],
+            "$defs": {
+                "TestStruct": TestStruct::json_schema()
+            }
+        });
+        assert_eq!(schema, expected);
+
+        let instance = json!({ "inner": { "name": "test", "age": 10, "scores": [] } });
+        assert!(jsonschema::is_valid(&schema, &instance));
+        let instance = json!({ "inner": { "name": "test" } });
+        assert!(!jsonschema::is_valid(&schema, &instance));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructOpenApi {
+        id: u32,
+        name: Option<String>,
+        age: Option<u32>,
+        tags: Option<Vec<bool>>,
+        inner: Option<TestStructOpenApiInner>,
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructOpenApiInner {
+        nickname: Option<String>,
+    }
+
+    #[test]
+    fn test_config_nullable_draft07() {
+        let config = JsonSchemaConfig {
+            draft: Draft::Draft07,
+            nullable: Nullable::Keyword,
+            ..Default::default()
+        };
+        let schema = TestStructOpenApiInner::json_schema_with(&config);
+        assert_eq!(
+            schema["properties"]["nickname"],
+            json!({ "type": "string", "nullable": true })
+        );
+        assert_eq!(schema["required"], json!([]));
+    }
+
+    #[test]
+    fn test_config_nullable_any_of() {
+        let schema = <Vec<Option<u32>>>::json_schema();
+        assert_eq!(
+            schema,
+            json!({ "type": "array", "items": { "type": "number" } })
+        );
+        assert!(!jsonschema::is_valid(&schema, &json!([1, null])));
+
+        let config = JsonSchemaConfig {
+            nullable: Nullable::AnyOf,
+            ..Default::default()
+        };
+        let schema = <Vec<Option<u32>>>::json_schema_with(&config);
+        assert_eq!(
+            schema,
+            json!({
+                "type": "array",
+                "items": { "anyOf": [{ "type": "number" }, { "type": "null" }] }
+            })
+        );
+        assert!(jsonschema::is_valid(&schema, &json!([1, null])));
+        assert!(!jsonschema::is_valid(&schema, &json!([1, "a"])));
+
+        let schema = TestStructOpenApi::json_schema_with(&config);
+        assert_eq!(
+            schema["properties"]["name"],
+            json!({ "anyOf": [{ "type": "string" }, { "type": "null" }] })
+        );
+        assert_eq!(schema["required"], json!(["id"]));
+        assert!(jsonschema::is_valid(
+            &schema,
+            &json!({ "id": 1, "name": null, "age": 3 })
+        ));
+    }
+
+    #[test]
+    fn test_config_omit_empty_required() {
+        let config = JsonSchemaConfig {
+            omit_empty_required: true,
+            ..Default::default()
+        };
+        let schema = NestedStruct::json_schema_with(&config);
+        assert!(schema.get("required").is_none());
+        assert_eq!(
+            schema["properties"]["inner"]["required"],
+            json!(["name", "age", "scores"])
+        );
+        assert_eq!(NestedStruct::json_schema()["required"], json!([]));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    enum EnumNamedPayload {
+        Item(TestStruct),
+        Pair { first: TestStruct, count: u32 },
+        Empty,
+    }
+
+    #[test]
+    fn test_config_defs_enum_variants() {
+        let config = JsonSchemaConfig {
+            inline: false,
+            ..Default::default()
+        };
+        let schema = EnumNamedPayload::json_schema_with(&config);
+        let reference = json!({ "$ref": "#/$defs/TestStruct" });
+        assert_eq!(schema["oneOf"][0]["properties"]["Item"], reference);
+        assert_eq!(
+            schema["oneOf"][1]["properties"]["Pair"]["properties"]["first"],
+            reference
+        );
+        assert_eq!(schema["$defs"]["TestStruct"], TestStruct::json_schema());
+        let instance = serde_json::to_value(EnumNamedPayload::Item(TestStruct {
+            name: "test".to_string(),
+            age: 10,
+            active: Some(true),
+            scores: vec![],
+        }))
+        .unwrap();
+        assert!(jsonschema::is_valid(&schema, &instance));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructBase {
+        id: u32,
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[json_schema(allOf = ["TestStructBase"])]
+    #[allow(dead_code)]
+    struct TestStructComposed {
+        name: String,
+    }
+
+    #[test]
+    fn test_struct_all_of_ref() {
+        let schema = TestStructComposed::json_schema();
+        assert_eq!(
+            schema["allOf"],
+            json!([{ "$ref": "#/$defs/TestStructBase" }])
+        );
+        assert_eq!(
+            schema["$defs"]["TestStructBase"],
+            TestStructBase::json_schema()
+        );
+        let instance = json!({ "id": 1, "name": "test" });
+        assert!(jsonschema::is_valid(&schema, &instance));
+        assert!(!jsonschema::is_valid(&schema, &json!({ "name": "test" })));
+    }
+
+    #[test]
+    fn test_config_nullable_keyword() {
+        let config = JsonSchemaConfig {
+            nullable: Nullable::Keyword,
+            ..Default::default()
+        };
+        let schema = TestStructOpenApi::json_schema_with(&config);
+        let expected = json!({
+            "type": "object",
+            "title": "TestStructOpenApi",
+            "properties": {
+                "id": { "type": "number" },
+                "name": { "type": "string", "nullable": true },
+                "age": { "type": "number", "nullable": true },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "boolean" },
+                    "nullable": true
+                },
+                "inner": {
+                    "type": "object",
+                    "title": "TestStructOpenApiInner",
+                    "properties": {
+                        "nickname": { "type": "string", "nullable": true }
+                    },
+                    "required": [],
+                    "nullable": true
+                }
+            },
+            "required": ["id"]
+        });
+        assert_eq!(schema, expected);
+
+        let config = JsonSchemaConfig {
+            nullable: Nullable::Keyword,
+            inline: false,
+            ..Default::default()
+        };
+        let schema = TestStructOpenApi::json_schema_with(&config);
+        assert_eq!(
+            schema["properties"]["inner"],
+            json!({
+                "allOf": [{ "$ref": "#/$defs/TestStructOpenApiInner" }],
+                "nullable": true
+            })
+        );
+
+        assert_eq!(
+            TestStructOpenApi::json_schema()["properties"]["name"],
+            json!({ "type": "string" })
+        );
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructInlineRef {
+        referenced: TestStructOpenApiInner,
+        #[json_schema(inline)]
+        inlined: TestStructOpenApiInner,
+        #[json_schema(ref)]
+        forced: TestStructUnnamedMultiple,
+    }
+
+    #[test]
+    fn test_field_inline_ref() {
+        let config = JsonSchemaConfig {
+            inline: false,
+            ..Default::default()
+        };
+        let schema = TestStructInlineRef::json_schema_with(&config);
+        assert_eq!(
+            schema["properties"]["referenced"],
+            json!({ "$ref": "#/$defs/TestStructOpenApiInner" })
+        );
+        assert_eq!(
+            schema["properties"]["inlined"],
+            TestStructOpenApiInner::json_schema()
+        );
+
+        let schema = TestStructInlineRef::json_schema();
+        assert_eq!(
+            schema["properties"]["referenced"],
+            TestStructOpenApiInner::json_schema()
+        );
+        assert_eq!(
+            schema["properties"]["forced"],
+            json!({ "$ref": "#/$defs/TestStructUnnamedMultiple" })
+        );
+        assert_eq!(
+            schema["$defs"]["TestStructUnnamedMultiple"],
+            TestStructUnnamedMultiple::json_schema()
+        );
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[json_schema(comment = "Test comment")]
+    #[allow(dead_code)]
+    struct TestStructUnnamed(String);
+
+    #[test]
+    fn test_struct_unnamed() {
+        let schema = TestStructUnnamed::json_schema();
+        let expected = json!({ "comment": "Test comment", "type": "string" });
+        assert_eq!(schema, expected);
+        assert!(valid(&TestStructUnnamed("test".to_string())));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[json_schema(comment = "Test comment")]
+    #[allow(dead_code)]
+    struct TestStructUnnamedMultiple(String, u32);
+
+    #[test]
+    fn test_struct_unnamed_multiple() {
+        let schema = TestStructUnnamedMultiple::json_schema();
         let expected = json!({
-            "type": "string",
             "comment": "Test comment",
-            "enum": ["A", "B", "C"],
+            "type": "array",
+            "prefixItems": [{ "type": "string" }, { "type": "number" }],
+            "minItems": 2,
+            "maxItems": 2,
+            "unevaluatedItems": false,
         });
-        println!("{:#?}", serde_json::to_value(&EnumUnit::A).unwrap());
         assert_eq!(schema, expected);
-        assert!(valid(&EnumUnit::A));
-        assert!(valid(&EnumUnit::B));
-        assert!(valid(&EnumUnit::C));
+        assert!(valid(&TestStructUnnamedMultiple("test".to_string(), 10)));
+    }
+
+    #[test]
+    fn test_schema() {
+        let schema = TestStruct::schema();
+        assert_eq!(schema.ty, Some(json!("object")));
+        assert_eq!(schema.extra["title"], json!("TestStruct"));
+        assert_eq!(
+            schema.required,
+            Some(vec!["name".into(), "age".into(), "scores".into()])
+        );
+        assert_eq!(serde_json::Value::from(schema), TestStruct::json_schema());
+
+        let schema = TestStructUnnamedMultiple::schema();
+        assert_eq!(schema.properties, None);
+        assert_eq!(
+            serde_json::Value::from(schema),
+            TestStructUnnamedMultiple::json_schema()
+        );
+    }
+
+    struct TestTypedSchema;
+
+    impl JsonSchema for TestTypedSchema {
+        fn json_schema() -> serde_json::Value {
+            let mut extra = serde_json::Map::new();
+            extra.insert("format".into(), json!("uri"));
+            Schema {
+                ty: Some(json!("string")),
+                extra,
+                ..Default::default()
+            }
+            .into()
+        }
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct TestStructTypedSchema {
+        link: TestTypedSchema,
+    }
+
+    #[test]
+    fn test_schema_typed_impl() {
+        let expected = json!({ "type": "string", "format": "uri" });
+        assert_eq!(TestTypedSchema::json_schema(), expected);
+        assert_eq!(TestTypedSchema::schema().ty, Some(json!("string")));
+        assert_eq!(
+            TestStructTypedSchema::json_schema()["properties"]["link"],
+            expected
+        );
+        assert_eq!(
+            bundle::<TestTypedSchema>(),
+            json!({
+                "$schema": Draft::Draft202012.uri(),
+                "type": "string",
+                "format": "uri"
+            })
+        );
+        #[cfg(feature = "validate")]
+        assert!(TestTypedSchema::validate(&json!(1)).is_err());
+    }
+
+    #[test]
+    fn test_json_schema_fragment() {
+        let (name, schema) = TestStructUnnamedMultiple::json_schema_fragment();
+        assert_eq!(name, "TestStructUnnamedMultiple");
+        assert_eq!(schema, TestStructUnnamedMultiple::json_schema());
+        for keyword in ["$schema", "$id", "$defs"] {
+            assert!(schema.get(keyword).is_none());
+        }
+        assert!(TestStructUnnamedMultiple::json_schema_fragment_defs().is_empty());
+
+        let (name, _) = <Vec<u32>>::json_schema_fragment();
+        assert_eq!(name, std::any::type_name::<Vec<u32>>());
+
+        let (_, schema) = TestStructComposed::json_schema_fragment();
+        assert_eq!(
+            schema["allOf"],
+            json!([{ "$ref": "#/$defs/TestStructBase" }])
+        );
+        assert!(schema.get("$defs").is_none());
+        let defs = TestStructComposed::json_schema_fragment_defs();
+        assert_eq!(defs["TestStructBase"], TestStructBase::json_schema());
+    }
+
+    #[test]
+    fn test_json_schema_ref() {
+        assert_eq!(TestStruct::json_schema_name(), "TestStruct");
+        assert_eq!(
+            TestStruct::json_schema_ref(),
+            json!({ "$ref": "#/$defs/TestStruct" })
+        );
+
+        let config = JsonSchemaConfig {
+            inline: false,
+            ..Default::default()
+        };
+        let schema = NestedStruct::json_schema_with(&config);
+        assert_eq!(schema["properties"]["inner"], TestStruct::json_schema_ref());
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[json_schema(id = "https://example.com/schemas/user.json")]
+    #[allow(dead_code)]
+    struct TestStructId {
+        inner: TestStructOpenApiInner,
+    }
+
+    #[test]
+    fn test_json_schema_id() {
+        let config = JsonSchemaConfig {
+            inline: false,
+            include_schema: true,
+            ..Default::default()
+        };
+        let schema = TestStructId::json_schema_with(&config);
+        let expected = json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "$id": "https://example.com/schemas/user.json",
+            "type": "object",
+            "title": "TestStructId",
+            "properties": {
+                "inner": { "$ref": "#/$defs/TestStructOpenApiInner" }
+            },
+            "required": ["inner"],
+            "$defs": {
+                "TestStructOpenApiInner": TestStructOpenApiInner::json_schema()
+            }
+        });
+        assert_eq!(schema, expected);
+        assert!(jsonschema::is_valid(
+            &schema,
+            &json!({ "inner": { "nickname": "test" } })
+        ));
+        assert_eq!(
+            TestStructId::json_schema_id(),
+            Some("https://example.com/schemas/user.json")
+        );
+
+        let (_, fragment) = TestStructId::json_schema_fragment();
+        assert!(fragment.get("$id").is_none());
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct Outer {
+            user: TestStructId,
+        }
+        assert!(Outer::json_schema()["properties"]["user"]
+            .get("$id")
+            .is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "validate")]
+    fn test_validate() {
+        assert_eq!(
+            TestStructReadWrite::validate(&json!({ "id": 1, "password": "secret" })),
+            Ok(())
+        );
+        assert_eq!(
+            TestStructReadWrite::validate(&json!({ "id": "1" })),
+            Err(vec![
+                r#""1" is not of type "number""#.to_string(),
+                r#""password" is a required property"#.to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "validate")]
+    fn test_validate_cached() {
+        let cell = TestStructReadWrite::validator_cell().unwrap();
+        let instance = json!({ "id": 1, "password": "secret" });
+        assert_eq!(TestStructReadWrite::validate(&instance), Ok(()));
+        let validator: *const jsonschema::Validator = cell.get().unwrap();
+        for _ in 0..1000 {
+            assert_eq!(TestStructReadWrite::validate(&instance), Ok(()));
+        }
+        assert!(std::ptr::eq(validator, cell.get().unwrap()));
+    }
+
+    #[test]
+    fn test_json_schema_string() {
+        let compact = TestStructUnnamedMultiple::json_schema_string();
+        assert!(!compact.contains('\n'));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&compact).unwrap(),
+            TestStructUnnamedMultiple::json_schema()
+        );
+
+        let pretty = TestStructUnnamedMultiple::json_schema_string_pretty();
+        assert!(pretty.contains('\n'));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&pretty).unwrap(),
+            TestStructUnnamedMultiple::json_schema()
+        );
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[json_schema(unevaluatedItems = true)]
+    #[allow(dead_code)]
+    struct TestStructUnnamedOpen(String, u32);
+
+    #[test]
+    fn test_struct_unnamed_open() {
+        let too_long = json!(["test", 10, true]);
+        let schema = TestStructUnnamedOpen::json_schema();
+        let expected = json!({
+            "type": "array",
+            "minItems": 2,
+            "prefixItems": [{ "type": "string" }, { "type": "number" }],
+            "unevaluatedItems": true
+        });
+        assert_eq!(schema, expected);
+        assert!(valid(&TestStructUnnamedOpen("test".to_string(), 10)));
+        assert!(jsonschema::is_valid(&schema, &too_long));
+
+        let config = JsonSchemaConfig {
+            draft: Draft::Draft07,
+            include_schema: true,
+            ..Default::default()
+        };
+        let schema = TestStructUnnamedOpen::json_schema_with(&config);
+        assert!(schema.get("additionalItems").is_none());
+        assert!(jsonschema::is_valid(&schema, &too_long));
+    }
+
+    #[test]
+    fn test_struct_unnamed_multiple_drafts() {
+        let instance =
+            serde_json::to_value(TestStructUnnamedMultiple("test".to_string(), 10)).unwrap();
+        let too_long = json!(["test", 10, true]);
+
+        let config = JsonSchemaConfig {
+            draft: Draft::Draft07,
+            include_schema: true,
+            ..Default::default()
+        };
+        let schema = TestStructUnnamedMultiple::json_schema_with(&config);
+        let expected = json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "comment": "Test comment",
+            "type": "array",
+            "items": [{ "type": "string" }, { "type": "number" }],
+            "minItems": 2,
+            "maxItems": 2,
+            "additionalItems": false,
+        });
+        assert_eq!(schema, expected);
+        assert!(jsonschema::is_valid(&schema, &instance));
+        assert!(!jsonschema::is_valid(&schema, &too_long));
+
+        let config = JsonSchemaConfig {
+            draft: Draft::Draft202012,
+            include_schema: true,
+            ..Default::default()
+        };
+        let schema = TestStructUnnamedMultiple::json_schema_with(&config);
+        let expected = json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "comment": "Test comment",
+            "type": "array",
+            "prefixItems": [{ "type": "string" }, { "type": "number" }],
+            "minItems": 2,
+            "maxItems": 2,
+            "unevaluatedItems": false,
+        });
+        assert_eq!(schema, expected);
+        assert!(jsonschema::is_valid(&schema, &instance));
+        assert!(!jsonschema::is_valid(&schema, &too_long));
+
+        let config = JsonSchemaConfig {
+            draft: Draft::Draft07,
+            ..Default::default()
+        };
+        assert_eq!(
+            <(u32, bool)>::json_schema_with(&config),
+            json!({
+                "type": "array",
+                "items": [{ "type": "number" }, { "type": "boolean" }],
+                "minItems": 2,
+                "maxItems": 2,
+                "additionalItems": false
+            })
+        );
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[json_schema(comment = "Test comment")]
+    #[allow(dead_code)]
+    enum EnumUnit {
+        A,
+        B,
+        C,
+    }
+
+    #[test]
+    fn test_enum_unit() {
+        let schema = EnumUnit::json_schema();
+        let expected = json!({
+            "type": "string",
+            "title": "EnumUnit",
+            "comment": "Test comment",
+            "enum": ["A", "B", "C"],
+        });
+        println!("{:#?}", serde_json::to_value(&EnumUnit::A).unwrap());
+        assert_eq!(schema, expected);
+        assert!(valid(&EnumUnit::A));
+        assert!(valid(&EnumUnit::B));
+        assert!(valid(&EnumUnit::C));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    enum EnumUnitDocumented {
+        /// Waiting to start
+        Pending,
+        /// Finished successfully
+        Done,
+        Failed,
+    }
+
+    #[test]
+    fn test_enum_unit_documented() {
+        let schema = EnumUnitDocumented::json_schema();
+        let expected = json!({
+            "type": "string",
+            "title": "EnumUnitDocumented",
+            "oneOf": [
+                { "const": "Pending", "description": "Waiting to start" },
+                { "const": "Done", "description": "Finished successfully" },
+                { "const": "Failed" }
+            ]
+        });
+        assert_eq!(schema, expected);
+        assert!(valid(&EnumUnitDocumented::Pending));
+        assert!(valid(&EnumUnitDocumented::Done));
+        assert!(valid(&EnumUnitDocumented::Failed));
+        assert!(!jsonschema::is_valid(&schema, &json!("Unknown")));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    enum EnumUnitAnnotated {
+        #[json_schema(comment = "Kept for old clients")]
+        Legacy,
+        Current,
+    }
+
+    #[test]
+    fn test_enum_unit_annotated() {
+        let schema = EnumUnitAnnotated::json_schema();
+        let expected = json!({
+            "type": "string",
+            "title": "EnumUnitAnnotated",
+            "oneOf": [
+                { "const": "Legacy", "comment": "Kept for old clients" },
+                { "const": "Current" }
+            ]
+        });
+        assert_eq!(schema, expected);
+        assert!(valid(&EnumUnitAnnotated::Legacy));
+        assert!(valid(&EnumUnitAnnotated::Current));
+    }
+
+    #[derive(JsonSchema)]
+    #[json_schema(repr = "int")]
+    #[allow(dead_code)]
+    enum EnumInt {
+        Ok = 200,
+        Created,
+        NotFound = 404,
+    }
+
+    #[test]
+    fn test_enum_int() {
+        let schema = EnumInt::json_schema();
+        let expected = json!({
+            "type": "number",
+            "title": "EnumInt",
+            "enum": [200, 201, 404],
+        });
+        assert_eq!(schema, expected);
+        assert!(jsonschema::is_valid(&schema, &json!(200)));
+        assert!(jsonschema::is_valid(&schema, &json!(404)));
+        assert!(!jsonschema::is_valid(&schema, &json!(500)));
+        assert!(!jsonschema::is_valid(&schema, &json!("Ok")));
+    }
+
+    #[test]
+    fn test_enum_int_bounds() {
+        let config = JsonSchemaConfig {
+            infer_integer_bounds: true,
+            ..Default::default()
+        };
+        let schema = EnumInt::json_schema_with(&config);
+        assert_eq!(schema["minimum"], json!(200));
+        assert_eq!(schema["maximum"], json!(404));
+        assert_eq!(schema["enum"], json!([200, 201, 404]));
+    }
+
+    #[test]
+    fn test_enum_int_integer_type() {
+        let config = JsonSchemaConfig {
+            integer_type: true,
+            ..Default::default()
+        };
+        let schema = EnumInt::json_schema_with(&config);
+        assert_eq!(schema["type"], json!("integer"));
+        assert!(jsonschema::is_valid(&schema, &json!(201)));
+        assert!(!jsonschema::is_valid(&schema, &json!(201.5)));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[json_schema(as = "array_of_variants")]
+    #[allow(dead_code)]
+    enum EnumFlags {
+        Read,
+        Write,
+        Execute,
+    }
+
+    #[test]
+    fn test_enum_flags() {
+        let schema = EnumFlags::json_schema();
+        let expected = json!({
+            "type": "array",
+            "title": "EnumFlags",
+            "items": { "type": "string", "enum": ["Read", "Write", "Execute"] },
+            "uniqueItems": true
+        });
+        assert_eq!(schema, expected);
+        let instance = serde_json::to_value([EnumFlags::Read, EnumFlags::Execute]).unwrap();
+        assert!(jsonschema::is_valid(&schema, &instance));
+        assert!(!jsonschema::is_valid(&schema, &json!(["Read", "Read"])));
+        assert!(!jsonschema::is_valid(&schema, &json!(["Delete"])));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[json_schema(variant_examples)]
+    #[allow(dead_code)]
+    enum EnumScalarExamples {
+        Name(String),
+        Count(u32),
+        Enabled(bool),
+    }
+
+    #[test]
+    fn test_enum_variant_examples() {
+        let schema = EnumScalarExamples::json_schema();
+        assert_eq!(
+            schema["examples"],
+            json!([{ "Name": "" }, { "Count": 0 }, { "Enabled": false }])
+        );
+        for example in schema["examples"].as_array().unwrap() {
+            assert!(jsonschema::is_valid(&schema, example));
+        }
+        assert!(valid(&EnumScalarExamples::Count(3)));
+
+        #[derive(JsonSchema)]
+        #[json_schema(variant_examples)]
+        #[allow(dead_code)]
+        enum EnumUnitExamples {
+            A,
+            B,
+        }
+        assert_eq!(
+            EnumUnitExamples::json_schema()["examples"],
+            json!(["A", "B"])
+        );
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[json_schema(comment = "Test comment")]
+    #[allow(dead_code)]
+    enum EnumUnnamed {
+        A(String),
+        B(u32),
+    }
+
+    #[test]
+    fn test_enum_unit_unnamed() {
+        let schema = EnumUnnamed::json_schema();
+        let expected = json!({
+            "title": "EnumUnnamed",
+            "comment": "Test comment",
+            "oneOf": [
+                {
+                    "type": "object",
+                    "properties": { "A": { "type": "string" } },
+                    "required": ["A"],
+                    "additionalProperties": false
+                },
+                {
+                    "type": "object",
+                    "properties": { "B": { "type": "number" } },
+                    "required": ["B"],
+                    "additionalProperties": false
+                }
+            ]
+        });
+        assert_eq!(schema, expected);
+        assert!(valid(&EnumUnnamed::A("test".to_string())));
+        assert!(valid(&EnumUnnamed::B(10)));
+        assert!(jsonschema::is_valid(&schema, &json!({ "A": "x" })));
+        assert!(jsonschema::is_valid(&schema, &json!({ "B": 1 })));
+        assert!(!jsonschema::is_valid(&schema, &json!({ "A": "x", "B": 1 })));
+        assert!(!jsonschema::is_valid(&schema, &json!({})));
+    }
+
+    #[derive(JsonSchema, Serialize, Deserialize, Debug, PartialEq)]
+    #[allow(dead_code)]
+    enum EnumMixed {
+        A(String),
+        B { age: u32 },
+        C(u32, bool),
+        D,
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    enum EnumUnitNamedUnnamed {
+        A,
+        B(u32),
+        C { x: String },
+    }
+
+    #[test]
+    fn test_enum_unit_named_unnamed() {
+        let schema = EnumUnitNamedUnnamed::json_schema();
+        let expected = json!({
+            "title": "EnumUnitNamedUnnamed",
+            "oneOf": [
+                { "type": "string", "const": "A" },
+                {
+                    "type": "object",
+                    "properties": { "B": { "type": "number" } },
+                    "required": ["B"],
+                    "additionalProperties": false
+                },
+                {
+                    "type": "object",
+                    "properties": {
+                        "C": {
+                            "type": "object",
+                            "title": "C",
+                            "properties": { "x": { "type": "string" } },
+                            "required": ["x"]
+                        }
+                    },
+                    "required": ["C"],
+                    "additionalProperties": false
+                }
+            ]
+        });
+        assert_eq!(schema, expected);
+        assert!(valid(&EnumUnitNamedUnnamed::A));
+        assert!(valid(&EnumUnitNamedUnnamed::B(1)));
+        assert!(valid(&EnumUnitNamedUnnamed::C {
+            x: "test".to_string()
+        }));
+    }
+
+    #[test]
+    fn test_enum_mixed_round_trip() {
+        let schema = EnumMixed::json_schema();
+        for value in [
+            EnumMixed::A("test".to_string()),
+            EnumMixed::B { age: 10 },
+            EnumMixed::C(1, true),
+            EnumMixed::D,
+        ] {
+            let instance = serde_json::to_value(&value).unwrap();
+            assert!(jsonschema::is_valid(&schema, &instance), "{instance}");
+            assert_eq!(
+                serde_json::from_value::<EnumMixed>(instance).unwrap(),
+                value
+            );
+        }
+        assert!(!jsonschema::is_valid(&schema, &json!("A")));
+        assert!(!jsonschema::is_valid(&schema, &json!({ "D": null })));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[json_schema(comment = "Test comment")]
+    #[allow(dead_code)]
+    enum EnumNamed {
+        A { name: String },
+        B { age: u32 },
+    }
+
+    #[test]
+    fn test_enum_named() {
+        let schema = EnumNamed::json_schema();
+        let expected = json!({
+            "title": "EnumNamed",
+            "comment": "Test comment",
+            "oneOf": [
+                {
+                    "type": "object",
+                    "properties": {
+                        "A": { "type": "object", "title": "A", "properties": { "name": { "type": "string" } }, "required": ["name"] }
+                    },
+                    "required": ["A"],
+                    "additionalProperties": false
+                },
+                {
+                    "type": "object",
+                    "properties": {
+                        "B": { "type": "object", "title": "B", "properties": { "age": { "type": "number" } }, "required": ["age"] }
+                    },
+                    "required": ["B"],
+                    "additionalProperties": false
+                }
+            ]
+        });
+        assert_eq!(schema, expected);
+        assert!(valid(&EnumNamed::A {
+            name: "test".to_string()
+        }));
+        assert!(valid(&EnumNamed::B { age: 10 }));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructTuple {
+        pos: (f64, f64),
+    }
+
+    #[test]
+    fn test_struct_tuple_field() {
+        let schema = TestStructTuple::json_schema();
+        let expected = json!({
+            "type": "object",
+            "title": "TestStructTuple",
+            "properties": {
+                "pos": {
+                    "type": "array",
+                    "prefixItems": [{ "type": "number" }, { "type": "number" }],
+                    "minItems": 2,
+                    "maxItems": 2,
+                    "unevaluatedItems": false
+                }
+            },
+            "required": ["pos"]
+        });
+        assert_eq!(schema, expected);
+        assert!(valid(&TestStructTuple { pos: (1.5, -2.0) }));
+        assert!(!jsonschema::is_valid(&schema, &json!({ "pos": [1.5] })));
+        assert!(!jsonschema::is_valid(
+            &schema,
+            &json!({ "pos": [1.5, 2.0, 3.0] })
+        ));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestSpecialItem(#[json_schema(minimum = 100)] u32);
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructContains {
+        #[json_schema(contains = "TestSpecialItem")]
+        items: Vec<u32>,
+    }
+
+    #[test]
+    fn test_struct_contains() {
+        let schema = TestStructContains::json_schema();
+        let expected = json!({
+            "type": "object",
+            "title": "TestStructContains",
+            "properties": {
+                "items": {
+                    "type": "array",
+                    "items": { "type": "number" },
+                    "contains": { "type": "number", "minimum": 100 }
+                }
+            },
+            "required": ["items"]
+        });
+        assert_eq!(schema, expected);
+        assert!(valid(&TestStructContains {
+            items: vec![1, 150]
+        }));
+        assert!(!valid(&TestStructContains { items: vec![1, 2] }));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructSkip {
+        name: String,
+        #[json_schema(skip)]
+        internal: u32,
+    }
+
+    #[test]
+    fn test_struct_skip() {
+        let schema = TestStructSkip::json_schema();
+        let expected = json!({
+            "type": "object",
+            "title": "TestStructSkip",
+            "properties": { "name": { "type": "string" } },
+            "required": ["name"]
+        });
+        assert_eq!(schema, expected);
+        assert!(valid(&TestStructSkip {
+            name: "test".to_string(),
+            internal: 0,
+        }));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructNestedGeneric {
+        counts: Vec<HashMap<String, u32>>,
+    }
+
+    #[test]
+    fn test_struct_nested_generic() {
+        let schema = TestStructNestedGeneric::json_schema();
+        let expected = json!({
+            "type": "object",
+            "title": "TestStructNestedGeneric",
+            "properties": {
+                "counts": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "additionalProperties": { "type": "number" }
+                    }
+                }
+            },
+            "required": ["counts"]
+        });
+        assert_eq!(schema, expected);
+        assert!(valid(&TestStructNestedGeneric {
+            counts: vec![HashMap::from([("a".to_string(), 1)]), HashMap::new()],
+        }));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructNestedOption {
+        boxed: Option<Box<u32>>,
+        qualified: std::option::Option<u32>,
+        double: Option<Option<u32>>,
+        count: u32,
+    }
+
+    mod user_option {
+        use super::*;
+
+        /// A user type that happens to be named `Option`.
+        #[derive(Serialize)]
+        pub struct Option<T>(pub T);
+
+        impl<T: JsonSchema> JsonSchema for Option<T> {
+            fn json_schema() -> serde_json::Value {
+                T::json_schema()
+            }
+        }
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructQualifiedOption {
+        core: core::option::Option<u32>,
+        user: user_option::Option<u32>,
+    }
+
+    #[test]
+    fn test_struct_qualified_option() {
+        let schema = TestStructQualifiedOption::json_schema();
+        assert_eq!(schema["required"], json!(["user"]));
+        assert!(valid(&TestStructQualifiedOption {
+            core: Some(1),
+            user: user_option::Option(1),
+        }));
+        assert!(!jsonschema::is_valid(&schema, &json!({ "core": 1 })));
+    }
+
+    #[test]
+    fn test_struct_nested_option() {
+        let schema = TestStructNestedOption::json_schema();
+        let expected = json!({
+            "type": "object",
+            "title": "TestStructNestedOption",
+            "properties": {
+                "boxed": { "type": "number" },
+                "qualified": { "type": "number" },
+                "double": { "type": "number" },
+                "count": { "type": "number" }
+            },
+            "required": ["count"]
+        });
+        assert_eq!(schema, expected);
+        assert!(valid(&TestStructNestedOption {
+            boxed: Some(Box::new(1)),
+            qualified: Some(2),
+            double: Some(Some(3)),
+            count: 4,
+        }));
+        assert!(jsonschema::is_valid(&schema, &json!({ "count": 4 })));
+    }
+
+    type MaybeInt = Option<i32>;
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructRequiredOverride {
+        // An alias of `Option` is not recognised and needs `optional`.
+        alias: MaybeInt,
+        #[json_schema(optional)]
+        optional_alias: MaybeInt,
+        #[json_schema(required)]
+        present: Option<String>,
+    }
+
+    #[test]
+    fn test_struct_required_override() {
+        let schema = TestStructRequiredOverride::json_schema();
+        assert_eq!(schema["required"], json!(["alias", "present"]));
+        assert_eq!(schema["properties"]["present"], json!({ "type": "string" }));
+        assert!(valid(&TestStructRequiredOverride {
+            alias: Some(1),
+            optional_alias: Some(2),
+            present: Some("test".to_string()),
+        }));
+        let instance = json!({ "alias": 1, "optional_alias": 2 });
+        assert!(!jsonschema::is_valid(&schema, &instance));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructOptionMatrix {
+        optional_list: Option<Vec<String>>,
+        list_of_optional: Vec<Option<u32>>,
+        double: Option<Option<u32>>,
+    }
+
+    #[test]
+    fn test_struct_option_matrix() {
+        let schema = TestStructOptionMatrix::json_schema();
+        assert_eq!(
+            schema["properties"],
+            json!({
+                "optional_list": { "type": "array", "items": { "type": "string" } },
+                "list_of_optional": { "type": "array", "items": { "type": "number" } },
+                "double": { "type": "number" }
+            })
+        );
+        assert_eq!(schema["required"], json!(["list_of_optional"]));
+        let instance = json!({ "optional_list": [], "list_of_optional": [] });
+        assert!(jsonschema::is_valid(&schema, &instance));
+        assert!(!jsonschema::is_valid(
+            &schema,
+            &json!({ "optional_list": [] })
+        ));
+
+        let config = JsonSchemaConfig {
+            nullable: Nullable::AnyOf,
+            ..Default::default()
+        };
+        let schema = TestStructOptionMatrix::json_schema_with(&config);
+        assert_eq!(
+            schema["properties"],
+            json!({
+                "optional_list": {
+                    "anyOf": [{ "type": "array", "items": { "type": "string" } }, { "type": "null" }]
+                },
+                "list_of_optional": {
+                    "type": "array",
+                    "items": { "anyOf": [{ "type": "number" }, { "type": "null" }] }
+                },
+                "double": {
+                    "anyOf": [
+                        { "anyOf": [{ "type": "number" }, { "type": "null" }] },
+                        { "type": "null" }
+                    ]
+                }
+            })
+        );
+        assert_eq!(schema["required"], json!(["list_of_optional"]));
+        let instance = TestStructOptionMatrix {
+            optional_list: None,
+            list_of_optional: vec![Some(1), None],
+            double: Some(None),
+        };
+        let instance = serde_json::to_value(&instance).unwrap();
+        assert!(jsonschema::is_valid(&schema, &instance));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[json_schema(deprecated)]
+    #[allow(dead_code)]
+    struct TestStructDeprecated {
+        #[json_schema(deprecated, comment = "Use name instead")]
+        username: String,
+        name: String,
+    }
+
+    #[test]
+    fn test_struct_deprecated() {
+        let schema = TestStructDeprecated::json_schema();
+        let expected = json!({
+            "type": "object",
+            "title": "TestStructDeprecated",
+            "deprecated": true,
+            "properties": {
+                "username": {
+                    "type": "string",
+                    "deprecated": true,
+                    "comment": "Use name instead"
+                },
+                "name": { "type": "string" }
+            },
+            "required": ["username", "name"]
+        });
+        assert_eq!(schema, expected);
+        assert!(valid(&TestStructDeprecated {
+            username: "test".to_string(),
+            name: "test".to_string(),
+        }));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructReadWrite {
+        #[json_schema(readOnly)]
+        id: u32,
+        #[json_schema(writeOnly)]
+        password: String,
+    }
+
+    #[test]
+    fn test_struct_read_only_write_only() {
+        let schema = TestStructReadWrite::json_schema();
+        assert_eq!(
+            schema["properties"]["id"],
+            json!({ "type": "number", "readOnly": true })
+        );
+        assert_eq!(
+            schema["properties"]["password"],
+            json!({ "type": "string", "writeOnly": true })
+        );
+        assert!(valid(&TestStructReadWrite {
+            id: 1,
+            password: "secret".to_string(),
+        }));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructMultipleAttributes {
+        #[json_schema(minLength = 2)]
+        #[json_schema(maxLength = 10)]
+        name: String,
+    }
+
+    #[test]
+    fn test_struct_multiple_attributes() {
+        let schema = TestStructMultipleAttributes::json_schema();
+        assert_eq!(
+            schema["properties"]["name"],
+            json!({ "type": "string", "minLength": 2, "maxLength": 10 })
+        );
+        assert!(valid(&TestStructMultipleAttributes {
+            name: "test".to_string(),
+        }));
+        assert!(!valid(&TestStructMultipleAttributes {
+            name: "t".to_string(),
+        }));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructConst {
+        #[json_schema(const = "v1")]
+        version: String,
+    }
+
+    #[test]
+    fn test_struct_const() {
+        let schema = TestStructConst::json_schema();
+        let expected = json!({
+            "type": "object",
+            "title": "TestStructConst",
+            "properties": { "version": { "type": "string", "const": "v1" } },
+            "required": ["version"]
+        });
+        assert_eq!(schema, expected);
+        assert!(valid(&TestStructConst {
+            version: "v1".to_string()
+        }));
+        assert!(!valid(&TestStructConst {
+            version: "v2".to_string()
+        }));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructWithSchema {
+        #[json_schema(with_schema = r#"{"type":"string","format":"uri"}"#)]
+        homepage: Vec<u8>,
+    }
+
+    #[test]
+    fn test_struct_with_schema() {
+        let schema = TestStructWithSchema::json_schema();
+        assert_eq!(
+            schema["properties"]["homepage"],
+            json!({ "type": "string", "format": "uri" })
+        );
+        assert!(jsonschema::is_valid(
+            &schema,
+            &json!({ "homepage": "https://example.com" })
+        ));
+        assert!(!jsonschema::is_valid(
+            &schema,
+            &json!({ "homepage": [1, 2] })
+        ));
+    }
+
+    mod custom_schema {
+        pub fn timestamp() -> serde_json::Value {
+            serde_json::json!({ "type": "string", "format": "date-time" })
+        }
+    }
+
+    #[allow(dead_code)]
+    struct ForeignTimestamp(u64);
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct TestStructWith {
+        #[json_schema(with = "custom_schema::timestamp", comment = "Creation time")]
+        created: ForeignTimestamp,
+    }
+
+    #[test]
+    fn test_struct_with() {
+        let schema = TestStructWith::json_schema();
+        assert_eq!(
+            schema["properties"]["created"],
+            json!({ "type": "string", "format": "date-time", "comment": "Creation time" })
+        );
+        assert_eq!(schema["required"], json!(["created"]));
+    }
+
+    fn color_schema() -> serde_json::Value {
+        json!({ "type": "string", "pattern": "^#[0-9a-f]{6}$" })
+    }
+
+    #[derive(JsonSchema, Serialize, Clone)]
+    #[serde(into = "String")]
+    #[json_schema(schema_fn = "color_schema")]
+    struct TestColor {
+        red: u8,
+        green: u8,
+        blue: u8,
+    }
+
+    impl From<TestColor> for String {
+        fn from(color: TestColor) -> Self {
+            format!("#{:02x}{:02x}{:02x}", color.red, color.green, color.blue)
+        }
+    }
+
+    #[test]
+    fn test_container_schema_fn() {
+        assert_eq!(TestColor::json_schema(), color_schema());
+        assert!(valid(&TestColor {
+            red: 255,
+            green: 128,
+            blue: 0,
+        }));
+        assert!(!jsonschema::is_valid(
+            &TestColor::json_schema(),
+            &json!({ "red": 255, "green": 128, "blue": 0 })
+        ));
+
+        let config = JsonSchemaConfig {
+            inline: false,
+            ..Default::default()
+        };
+        let mut defs = Definitions::new();
+        assert_eq!(
+            TestColor::json_schema_in(&config, &mut defs),
+            json!({ "$ref": "#/$defs/TestColor" })
+        );
+        assert_eq!(defs["TestColor"], color_schema());
+    }
+
+    #[allow(dead_code)]
+    trait Shape {
+        fn area(&self) -> f64;
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct TestStructSchemaOverride {
+        name: String,
+        #[json_schema(
+            schema = r#"{ "type": "object", "properties": { "area": { "type": "number" } } }"#,
+            comment = "Any shape"
+        )]
+        shape: Option<Box<dyn Shape>>,
+    }
+
+    #[test]
+    fn test_struct_schema_override() {
+        let schema = TestStructSchemaOverride::json_schema();
+        let expected = json!({
+            "type": "object",
+            "title": "TestStructSchemaOverride",
+            "properties": {
+                "name": { "type": "string" },
+                "shape": {
+                    "type": "object",
+                    "properties": { "area": { "type": "number" } },
+                    "comment": "Any shape"
+                }
+            },
+            "required": ["name"]
+        });
+        assert_eq!(schema, expected);
+        assert!(jsonschema::is_valid(&schema, &json!({ "name": "test" })));
+        assert!(jsonschema::is_valid(
+            &schema,
+            &json!({ "name": "test", "shape": { "area": 1.5 } })
+        ));
+        assert!(!jsonschema::is_valid(
+            &schema,
+            &json!({ "name": "test", "shape": { "area": "large" } })
+        ));
+    }
+
+    #[derive(Serialize)]
+    #[serde(untagged)]
+    #[allow(dead_code)]
+    enum TestLiteral {
+        Number(u32),
+        String(String),
+        Bool(bool),
+    }
+
+    impl JsonSchema for TestLiteral {
+        fn json_schema() -> serde_json::Value {
+            json!({})
+        }
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructEnumValues {
+        #[json_schema(enum_values = [1, "two", true])]
+        value: TestLiteral,
+        #[json_schema(enum_values = ["a", "b"])]
+        name: String,
+    }
+
+    #[test]
+    fn test_struct_enum_values() {
+        let schema = TestStructEnumValues::json_schema();
+        let expected = json!({
+            "type": "object",
+            "title": "TestStructEnumValues",
+            "properties": {
+                "value": { "enum": [1, "two", true] },
+                "name": { "enum": ["a", "b"] }
+            },
+            "required": ["value", "name"]
+        });
+        assert_eq!(schema, expected);
+        assert!(valid(&TestStructEnumValues {
+            value: TestLiteral::Number(1),
+            name: "a".to_string(),
+        }));
+        assert!(valid(&TestStructEnumValues {
+            value: TestLiteral::String("two".to_string()),
+            name: "b".to_string(),
+        }));
+        assert!(valid(&TestStructEnumValues {
+            value: TestLiteral::Bool(true),
+            name: "a".to_string(),
+        }));
+        assert!(!valid(&TestStructEnumValues {
+            value: TestLiteral::Number(2),
+            name: "a".to_string(),
+        }));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructExamples {
+        #[json_schema(examples = ["alice", "bob"])]
+        name: String,
+    }
+
+    #[test]
+    fn test_struct_examples() {
+        let schema = TestStructExamples::json_schema();
+        assert_eq!(
+            schema["properties"]["name"],
+            json!({ "type": "string", "examples": ["alice", "bob"] })
+        );
+        assert!(valid(&TestStructExamples {
+            name: "carol".to_string()
+        }));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructExample {
+        #[json_schema(example = "alice")]
+        name: String,
+        #[json_schema(examples = [1, 2])]
+        age: u32,
+    }
+
+    #[test]
+    fn test_struct_example_drafts() {
+        let expected = json!({
+            "name": { "type": "string", "examples": ["alice"] },
+            "age": { "type": "number", "examples": [1, 2] }
+        });
+        for draft in [Draft::Draft07, Draft::Draft201909, Draft::Draft202012] {
+            let config = JsonSchemaConfig {
+                draft,
+                include_schema: true,
+                ..Default::default()
+            };
+            let schema = TestStructExample::json_schema_with(&config);
+            assert_eq!(schema["properties"], expected);
+            assert!(jsonschema::is_valid(
+                &schema,
+                &json!({ "name": "bob", "age": 3 })
+            ));
+        }
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructItems {
+        #[json_schema(minItems = 1, maxItems = 3, uniqueItems = true)]
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn test_struct_items() {
+        let schema = TestStructItems::json_schema();
+        assert_eq!(
+            schema["properties"]["tags"],
+            json!({
+                "type": "array",
+                "items": { "type": "string" },
+                "minItems": 1,
+                "maxItems": 3,
+                "uniqueItems": true
+            })
+        );
+        assert!(valid(&TestStructItems {
+            tags: vec!["a".to_string()]
+        }));
+        assert!(!valid(&TestStructItems { tags: vec![] }));
+        assert!(!valid(&TestStructItems {
+            tags: vec![
+                "a".to_string(),
+                "b".to_string(),
+                "c".to_string(),
+                "d".to_string()
+            ]
+        }));
+    }
+
+    mod crate_path {
+        use crate::JsonSchema;
+
+        pub use ::serde_json as my_json;
+
+        // Shadows the real crate, so the derive has to go through the configured path.
+        #[allow(dead_code)]
+        mod serde_json {}
+
+        #[derive(JsonSchema)]
+        #[json_schema(crate = "my_json")]
+        #[allow(dead_code)]
+        pub struct TestStructCrate {
+            name: String,
+        }
+    }
+
+    #[test]
+    fn test_struct_crate_path() {
+        let schema = crate_path::TestStructCrate::json_schema();
+        let expected = json!({
+            "type": "object",
+            "title": "TestStructCrate",
+            "properties": { "name": { "type": "string" } },
+            "required": ["name"]
+        });
+        assert_eq!(schema, expected);
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructSet {
+        ids: HashSet<u32>,
+        names: BTreeSet<String>,
+    }
+
+    #[test]
+    fn test_struct_set() {
+        let schema = TestStructSet::json_schema();
+        assert_eq!(
+            schema["properties"]["ids"],
+            json!({ "type": "array", "items": { "type": "number" }, "uniqueItems": true })
+        );
+        assert_eq!(
+            schema["properties"]["names"],
+            json!({ "type": "array", "items": { "type": "string" }, "uniqueItems": true })
+        );
+        assert!(valid(&TestStructSet {
+            ids: HashSet::from([1, 2, 3]),
+            names: BTreeSet::from(["a".to_string()]),
+        }));
+        let instance = json!({ "ids": [1, 1], "names": [] });
+        assert!(!jsonschema::is_valid(&schema, &instance));
+        let instance = json!({ "ids": [], "names": ["a", "a"] });
+        assert!(!jsonschema::is_valid(&schema, &instance));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructMap {
+        #[json_schema(key_min_length = 1, key_max_length = 3)]
+        counts: HashMap<String, u32>,
+        labels: BTreeMap<String, String>,
+    }
+
+    #[test]
+    fn test_struct_map() {
+        let schema = TestStructMap::json_schema();
+        assert_eq!(
+            schema["properties"]["counts"],
+            json!({
+                "type": "object",
+                "additionalProperties": { "type": "number" },
+                "propertyNames": { "minLength": 1, "maxLength": 3 }
+            })
+        );
+        assert_eq!(
+            schema["properties"]["labels"],
+            json!({ "type": "object", "additionalProperties": { "type": "string" } })
+        );
+        assert!(valid(&TestStructMap {
+            counts: HashMap::from([("abc".to_string(), 1)]),
+            labels: BTreeMap::new(),
+        }));
+        assert!(!valid(&TestStructMap {
+            counts: HashMap::from([("abcd".to_string(), 1)]),
+            labels: BTreeMap::new(),
+        }));
+        assert!(!valid(&TestStructMap {
+            counts: HashMap::from([(String::new(), 1)]),
+            labels: BTreeMap::new(),
+        }));
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct TestStructBytes {
+        raw: Vec<u8>,
+        #[json_schema(bytes = "base64")]
+        encoded: Vec<u8>,
+        #[json_schema(bytes = "base64")]
+        digest: [u8; 4],
+    }
+
+    #[test]
+    fn test_struct_bytes() {
+        let schema = TestStructBytes::json_schema();
+        assert_eq!(
+            schema["properties"]["raw"],
+            json!({ "type": "array", "items": { "type": "number" } })
+        );
+        let base64 = json!({ "type": "string", "contentEncoding": "base64" });
+        assert_eq!(schema["properties"]["encoded"], base64);
+        assert_eq!(schema["properties"]["digest"], base64);
+        let instance = json!({ "raw": [1, 2], "encoded": "AQI=", "digest": "AQIDBA==" });
+        assert!(jsonschema::is_valid(&schema, &instance));
+        let instance = json!({ "raw": [1, 2], "encoded": [1, 2], "digest": "AQIDBA==" });
+        assert!(!jsonschema::is_valid(&schema, &instance));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructContent {
+        #[json_schema(contentEncoding = "base64", contentMediaType = "image/png")]
+        image: String,
+    }
+
+    #[test]
+    fn test_struct_content() {
+        let schema = TestStructContent::json_schema();
+        assert_eq!(
+            schema["properties"]["image"],
+            json!({
+                "type": "string",
+                "contentEncoding": "base64",
+                "contentMediaType": "image/png"
+            })
+        );
+        assert!(valid(&TestStructContent {
+            image: "iVBORw0KGgo=".to_string()
+        }));
+    }
+
+    #[derive(JsonSchema, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+    #[allow(dead_code)]
+    enum Color {
+        Red,
+        Green,
+    }
+
+    #[test]
+    fn test_map_enum_keys() {
+        let schema = BTreeMap::<Color, u32>::json_schema();
+        let expected = json!({
+            "type": "object",
+            "propertyNames": { "enum": ["Red", "Green"] },
+            "additionalProperties": { "type": "number" }
+        });
+        assert_eq!(schema, expected);
+        assert!(valid(&BTreeMap::from([(Color::Red, 1), (Color::Green, 2)])));
+        assert!(!jsonschema::is_valid(&schema, &json!({ "Blue": 1 })));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructMapSize {
+        #[json_schema(minProperties = 1, maxProperties = 5)]
+        counts: HashMap<String, u32>,
+    }
+
+    #[test]
+    fn test_struct_map_size() {
+        let schema = TestStructMapSize::json_schema();
+        assert_eq!(
+            schema["properties"]["counts"],
+            json!({
+                "type": "object",
+                "additionalProperties": { "type": "number" },
+                "minProperties": 1,
+                "maxProperties": 5
+            })
+        );
+        assert!(valid(&TestStructMapSize {
+            counts: HashMap::from([("a".to_string(), 1)]),
+        }));
+        assert!(!valid(&TestStructMapSize {
+            counts: HashMap::new(),
+        }));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructPatternProperties {
+        #[json_schema(patternProperties = { "^S_": { "type": "string" } })]
+        values: HashMap<String, serde_json::Value>,
+    }
+
+    #[test]
+    fn test_struct_pattern_properties() {
+        let schema = TestStructPatternProperties::json_schema();
+        assert_eq!(
+            schema["properties"]["values"],
+            json!({
+                "type": "object",
+                "additionalProperties": {},
+                "patternProperties": { "^S_": { "type": "string" } }
+            })
+        );
+        assert!(valid(&TestStructPatternProperties {
+            values: HashMap::from([
+                ("S_name".to_string(), json!("test")),
+                ("count".to_string(), json!(1)),
+            ]),
+        }));
+        assert!(!valid(&TestStructPatternProperties {
+            values: HashMap::from([("S_name".to_string(), json!(1))]),
+        }));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructMapPattern {
+        #[json_schema(propertyNames_pattern = "^[a-z]+$")]
+        counts: HashMap<String, u32>,
+    }
+
+    #[test]
+    fn test_struct_map_pattern() {
+        let schema = TestStructMapPattern::json_schema();
+        assert_eq!(
+            schema["properties"]["counts"],
+            json!({
+                "type": "object",
+                "additionalProperties": { "type": "number" },
+                "propertyNames": { "pattern": "^[a-z]+$" }
+            })
+        );
+        assert!(valid(&TestStructMapPattern {
+            counts: HashMap::from([("abc".to_string(), 1)]),
+        }));
+        assert!(!valid(&TestStructMapPattern {
+            counts: HashMap::from([("aBc".to_string(), 1)]),
+        }));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructWide {
+        large: u128,
+        small: i128,
+    }
+
+    #[test]
+    fn test_struct_wide_integers() {
+        assert_eq!(<u128>::json_schema(), json!({ "type": "number" }));
+        assert_eq!(<i128>::json_schema(), json!({ "type": "number" }));
+        assert!(valid(&TestStructWide {
+            large: u64::MAX as u128,
+            small: i64::MIN as i128,
+        }));
+    }
+
+    #[test]
+    fn test_config_infer_integer_bounds() {
+        let config = JsonSchemaConfig {
+            integer_type: true,
+            infer_integer_bounds: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            <u8>::json_schema_with(&config),
+            json!({ "type": "integer", "minimum": 0, "maximum": 255 })
+        );
+        assert_eq!(
+            <i16>::json_schema_with(&config),
+            json!({ "type": "integer", "minimum": -32768, "maximum": 32767 })
+        );
+        assert_eq!(
+            <u64>::json_schema_with(&config),
+            json!({ "type": "integer", "minimum": 0, "maximum": u64::MAX })
+        );
+        assert_eq!(
+            <u128>::json_schema_with(&config),
+            json!({ "type": "integer", "minimum": 0 })
+        );
+        assert_eq!(
+            <i128>::json_schema_with(&config),
+            json!({ "type": "integer" })
+        );
+
+        #[derive(JsonSchema, Serialize)]
+        struct Pixel {
+            red: u8,
+        }
+        let schema = Pixel::json_schema_with(&config);
+        assert!(jsonschema::is_valid(&schema, &json!({ "red": 255 })));
+        assert!(!jsonschema::is_valid(&schema, &json!({ "red": 256 })));
+        assert!(!jsonschema::is_valid(&schema, &json!({ "red": -1 })));
+    }
+
+    #[test]
+    fn test_config_integer_type() {
+        let config = JsonSchemaConfig {
+            integer_type: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            <u8>::json_schema_with(&config),
+            json!({ "type": "integer" })
+        );
+        assert_eq!(
+            <i128>::json_schema_with(&config),
+            json!({ "type": "integer" })
+        );
+        assert_eq!(
+            <f64>::json_schema_with(&config),
+            json!({ "type": "number" })
+        );
+
+        let schema = TestStructReadWrite::json_schema_with(&config);
+        assert_eq!(
+            schema["properties"]["id"],
+            json!({ "type": "integer", "readOnly": true })
+        );
+        let instance = json!({ "id": 3.5, "password": "secret" });
+        assert!(!jsonschema::is_valid(&schema, &instance));
+        let instance = json!({ "id": 3, "password": "secret" });
+        assert!(jsonschema::is_valid(&schema, &instance));
+
+        let schema = TestStructReadWrite::json_schema();
+        assert!(jsonschema::is_valid(
+            &schema,
+            &json!({ "id": 3.5, "password": "secret" })
+        ));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructTime {
+        timeout: Duration,
+        created: SystemTime,
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructIntegerField {
+        #[json_schema(integer)]
+        count: u32,
+        #[json_schema(integer)]
+        limit: Option<u64>,
+        ratio: f64,
+        total: u32,
+    }
+
+    #[test]
+    fn test_struct_integer_field() {
+        let schema = TestStructIntegerField::json_schema();
+        assert_eq!(
+            schema["properties"],
+            json!({
+                "count": { "type": "integer" },
+                "limit": { "type": "integer" },
+                "ratio": { "type": "number" },
+                "total": { "type": "number" }
+            })
+        );
+        assert!(valid(&TestStructIntegerField {
+            count: 1,
+            limit: Some(2),
+            ratio: 0.5,
+            total: 3,
+        }));
+        let instance = json!({ "count": 1.5, "ratio": 0.5, "total": 3 });
+        assert!(!jsonschema::is_valid(&schema, &instance));
+
+        let config = JsonSchemaConfig {
+            nullable: Nullable::AnyOf,
+            ..Default::default()
+        };
+        let schema = TestStructIntegerField::json_schema_with(&config);
+        assert_eq!(
+            schema["properties"]["limit"],
+            json!({ "anyOf": [{ "type": "integer" }, { "type": "null" }] })
+        );
+        let instance = json!({ "count": 1, "limit": 2.5, "ratio": 0.5, "total": 3 });
+        assert!(!jsonschema::is_valid(&schema, &instance));
+        let instance = json!({ "count": 1, "limit": null, "ratio": 0.5, "total": 3 });
+        assert!(jsonschema::is_valid(&schema, &instance));
+    }
+
+    #[test]
+    fn test_result() {
+        let schema = Result::<u32, String>::json_schema();
+        let expected = json!({
+            "oneOf": [
+                { "type": "object", "properties": { "Ok": { "type": "number" } }, "required": ["Ok"], "additionalProperties": false },
+                { "type": "object", "properties": { "Err": { "type": "string" } }, "required": ["Err"], "additionalProperties": false }
+            ]
+        });
+        assert_eq!(schema, expected);
+        assert!(valid(&Result::<u32, String>::Ok(1)));
+        assert!(valid(&Result::<u32, String>::Err("failed".to_string())));
+        assert!(!jsonschema::is_valid(&schema, &json!({ "Ok": "failed" })));
+        assert!(!jsonschema::is_valid(
+            &schema,
+            &json!({ "Ok": 1, "Err": "failed" })
+        ));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[json_schema("x-foo" = "bar", description = "Vendor extensions", "x-meta" = { "a": 1 })]
+    #[allow(dead_code)]
+    struct TestStructVendorExtension {
+        #[json_schema("x-internal" = true, "x-tags" = ["a", 1])]
+        name: String,
+    }
+
+    #[test]
+    fn test_struct_vendor_extension() {
+        let schema = TestStructVendorExtension::json_schema();
+        assert_eq!(schema["x-foo"], json!("bar"));
+        assert_eq!(schema["x-meta"], json!({ "a": 1 }));
+        assert_eq!(schema["description"], json!("Vendor extensions"));
+        assert_eq!(
+            schema["properties"]["name"],
+            json!({ "type": "string", "x-internal": true, "x-tags": ["a", 1] })
+        );
+        assert!(valid(&TestStructVendorExtension {
+            name: "test".to_string()
+        }));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[json_schema(dependentRequired(credit_card = ["billing_address"]))]
+    #[allow(dead_code)]
+    struct TestStructDependentRequired {
+        name: String,
+        credit_card: Option<String>,
+        billing_address: Option<String>,
+    }
+
+    #[test]
+    fn test_struct_dependent_required() {
+        let schema = TestStructDependentRequired::json_schema();
+        assert_eq!(
+            schema["dependentRequired"],
+            json!({ "credit_card": ["billing_address"] })
+        );
+        assert!(valid(&TestStructDependentRequired {
+            name: "test".to_string(),
+            credit_card: Some("4111".to_string()),
+            billing_address: Some("Main Street".to_string()),
+        }));
+        let instance = json!({ "name": "test", "credit_card": "4111" });
+        assert!(!jsonschema::is_valid(&schema, &instance));
+        assert!(jsonschema::is_valid(&schema, &json!({ "name": "test" })));
+    }
+
+    const MAX_NAME_LEN: usize = 8;
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructConstValue {
+        #[json_schema(maxLength = MAX_NAME_LEN)]
+        name: String,
+        #[json_schema(key_max_length = MAX_NAME_LEN)]
+        counts: HashMap<String, u32>,
+    }
+
+    #[test]
+    fn test_struct_const_value() {
+        let schema = TestStructConstValue::json_schema();
+        assert_eq!(schema["properties"]["name"]["maxLength"], json!(8));
+        assert_eq!(
+            schema["properties"]["counts"]["propertyNames"],
+            json!({ "maxLength": 8 })
+        );
+        assert!(valid(&TestStructConstValue {
+            name: "test".to_string(),
+            counts: HashMap::new(),
+        }));
+        assert!(!valid(&TestStructConstValue {
+            name: "too long name".to_string(),
+            counts: HashMap::new(),
+        }));
+    }
+
+    #[cfg(all(feature = "decimal", not(feature = "decimal-float")))]
+    #[test]
+    fn test_decimal() {
+        use rust_decimal::Decimal;
+
+        let schema = Decimal::json_schema();
+        assert_eq!(
+            schema,
+            json!({ "type": "string", "pattern": "^-?\\d+(\\.\\d+)?$" })
+        );
+        assert!(valid(&Decimal::new(-12345, 2)));
+        assert!(jsonschema::is_valid(&schema, &json!("10")));
+        assert!(!jsonschema::is_valid(&schema, &json!("1.2.3")));
+        assert!(!jsonschema::is_valid(&schema, &json!(1.5)));
+    }
+
+    #[cfg(feature = "decimal-float")]
+    #[test]
+    fn test_decimal_float() {
+        use rust_decimal::Decimal;
+
+        assert_eq!(Decimal::json_schema(), json!({ "type": "number" }));
+        assert!(valid(&Decimal::new(-12345, 2)));
+    }
+
+    #[test]
+    fn test_struct_time() {
+        let schema = TestStructTime::json_schema();
+        assert_eq!(schema["required"], json!(["timeout", "created"]));
+        assert!(valid(&TestStructTime {
+            timeout: Duration::from_millis(1500),
+            created: SystemTime::now(),
+        }));
+        assert!(!jsonschema::is_valid(
+            &Duration::json_schema(),
+            &json!({ "secs": 1 })
+        ));
+        assert!(!jsonschema::is_valid(&Duration::json_schema(), &json!(1.5)));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructPath<'a> {
+        config: PathBuf,
+        log: &'a Path,
+    }
+
+    #[test]
+    fn test_struct_path() {
+        assert_eq!(PathBuf::json_schema(), json!({ "type": "string" }));
+        assert_eq!(<&Path>::json_schema(), json!({ "type": "string" }));
+        assert!(valid(&TestStructPath {
+            config: PathBuf::from("/etc/app/config.toml"),
+            log: Path::new("logs/app.log"),
+        }));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructAny {
+        /// Arbitrary payload
+        payload: serde_json::Value,
+        extra: serde_json::Map<String, serde_json::Value>,
+    }
+
+    #[test]
+    fn test_struct_any() {
+        let schema = TestStructAny::json_schema();
+        assert_eq!(
+            schema["properties"],
+            json!({
+                "payload": { "description": "Arbitrary payload" },
+                "extra": { "type": "object" }
+            })
+        );
+        for payload in [json!(null), json!(1), json!("a"), json!([1, { "b": true }])] {
+            assert!(valid(&TestStructAny {
+                payload,
+                extra: serde_json::Map::new(),
+            }));
+        }
+        assert!(!jsonschema::is_valid(
+            &schema,
+            &json!({ "payload": 1, "extra": [] })
+        ));
+    }
+
+    #[test]
+    fn test_slice() {
+        let expected = json!({ "type": "array", "items": { "type": "number" } });
+        assert_eq!(<[u32]>::json_schema(), expected);
+        assert_eq!(<&[u32]>::json_schema(), expected);
+        assert_eq!(<&str>::json_schema(), json!({ "type": "string" }));
+        assert_eq!(<&Option<bool>>::json_schema(), json!({ "type": "boolean" }));
+
+        let scores = vec![1, 2, 3];
+        assert!(valid::<&[u32]>(&scores.as_slice()));
+        assert!(valid::<&[u32]>(&&scores[..1]));
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct TestStructPhantom {
+        name: String,
+        marker: PhantomData<u8>,
+    }
+
+    #[test]
+    fn test_struct_phantom_data() {
+        assert_eq!(<PhantomData<u8>>::json_schema(), json!({ "type": "null" }));
+        let schema = TestStructPhantom::json_schema();
+        let expected = json!({
+            "type": "object",
+            "title": "TestStructPhantom",
+            "properties": { "name": { "type": "string" } },
+            "required": ["name"]
+        });
+        assert_eq!(schema, expected);
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestRow {
+        id: u32,
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructRows {
+        rows: [TestRow; 4],
+    }
+
+    #[test]
+    fn test_struct_array_of_structs() {
+        let schema = TestStructRows::json_schema();
+        assert_eq!(
+            schema["properties"]["rows"],
+            json!({
+                "type": "array",
+                "items": TestRow::json_schema(),
+                "minItems": 4,
+                "maxItems": 4
+            })
+        );
+        assert!(valid(&TestStructRows {
+            rows: [
+                TestRow { id: 1 },
+                TestRow { id: 2 },
+                TestRow { id: 3 },
+                TestRow { id: 4 }
+            ]
+        }));
+        let instance = json!({ "rows": [{ "id": 1 }, { "id": 2 }, { "id": 3 }] });
+        assert!(!jsonschema::is_valid(&schema, &instance));
+        let instance = json!({ "rows": [{ "id": 1 }, { "id": 2 }, { "id": 3 }, {}] });
+        assert!(!jsonschema::is_valid(&schema, &instance));
+    }
+
+    #[derive(JsonSchema)]
+    #[json_schema(source_comment)]
+    #[allow(dead_code)]
+    struct User {
+        name: String,
+    }
+
+    #[test]
+    fn test_struct_source_comment() {
+        let schema = User::json_schema();
+        assert_eq!(schema["$comment"], json!("Generated from Rust type User"));
+        assert!(TestStruct::json_schema().get("$comment").is_none());
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    struct TestStructCow<'a> {
+        name: Cow<'a, str>,
+        scores: Cow<'a, [u32]>,
+    }
+
+    #[test]
+    fn test_cow() {
+        assert_eq!(<Cow<str>>::json_schema(), json!({ "type": "string" }));
+        assert_eq!(
+            <Cow<[u32]>>::json_schema(),
+            json!({ "type": "array", "items": { "type": "number" } })
+        );
+        let scores = [1, 2, 3];
+        assert!(valid(&TestStructCow {
+            name: Cow::Borrowed("test"),
+            scores: Cow::Borrowed(&scores),
+        }));
+        assert!(valid(&TestStructCow {
+            name: Cow::Owned("test".to_string()),
+            scores: Cow::Owned(vec![1]),
+        }));
+        assert_eq!(
+            TestStructCow::json_schema()["properties"],
+            json!({
+                "name": { "type": "string" },
+                "scores": { "type": "array", "items": { "type": "number" } }
+            })
+        );
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    /// Test description
+    struct TestStructDoc {
+        /// Test field description
+        name: String,
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[json_schema(comment = "Test comment")]
+    #[allow(dead_code)]
+    /// Test description
+    struct TestStructKeyOrder {
+        name: String,
+    }
+
+    // Keys keep their insertion order only with `preserve_order`; the default map
+    // backend sorts them.
+    #[test]
+    #[cfg(feature = "preserve_order")]
+    fn test_struct_key_order() {
+        let schema = serde_json::to_string(&TestStructKeyOrder::json_schema()).unwrap();
+        assert_eq!(
+            schema,
+            concat!(
+                r#"{"type":"object","title":"TestStructKeyOrder","description":"Test description","#,
+                r#""properties":{"name":{"type":"string"}},"required":["name"],"comment":"Test comment"}"#
+            )
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "preserve_order"))]
+    fn test_struct_key_order() {
+        let schema = TestStructKeyOrder::json_schema();
+        let keys: Vec<_> = schema.as_object().unwrap().keys().collect();
+        assert_eq!(
+            keys,
+            [
+                "comment",
+                "description",
+                "properties",
+                "required",
+                "title",
+                "type"
+            ]
+        );
+        let schema = serde_json::to_string(&schema).unwrap();
+        assert_eq!(
+            schema,
+            serde_json::to_string(&TestStructKeyOrder::json_schema()).unwrap()
+        );
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructRawIdent {
+        r#type: String,
+        r#ref: Option<u32>,
+    }
+
+    #[test]
+    fn test_struct_raw_ident() {
+        let schema = TestStructRawIdent::json_schema();
+        assert_eq!(
+            schema["properties"],
+            json!({ "type": { "type": "string" }, "ref": { "type": "number" } })
+        );
+        assert_eq!(schema["required"], json!(["type"]));
+        assert!(valid(&TestStructRawIdent {
+            r#type: "test".to_string(),
+            r#ref: Some(1),
+        }));
+    }
+
+    #[test]
+    fn test_struct_doc() {
+        let schema = TestStructDoc::json_schema();
+        let expected = json!({ "type": "object", "title": "TestStructDoc", "description": "Test description", "properties": { "name": { "type": "string", "description": "Test field description" } }, "required": ["name"] });
+        assert_eq!(schema, expected);
+        assert!(valid(&TestStructDoc {
+            name: "test".to_string()
+        }));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    /// First line
+    ///   Second line
+    /// Third line
+    struct TestStructDocMultiline {
+        name: String,
+    }
+
+    #[test]
+    fn test_struct_doc_multiline() {
+        let schema = TestStructDocMultiline::json_schema();
+        assert_eq!(
+            schema["description"],
+            json!("First line\nSecond line\nThird line")
+        );
+    }
+}
+
+#[cfg(feature = "serde-compat")]
+#[cfg(test)]
+mod tests_serde_compat {
+    use super::*;
+    use serde::Serialize;
+    use serde_json::json;
+
+    #[derive(JsonSchema, Serialize)]
+    #[json_schema(comment = "Test comment")]
+    #[allow(dead_code)]
+    struct TestStructWithSerde {
+        #[serde(skip)]
+        skip: u32,
+        #[serde(rename = "foo")]
+        renamed: u32,
+    }
+
+    #[test]
+    fn test_struct_with_serde() {
+        let schema = TestStructWithSerde::json_schema();
+        let expected = json!({
+            "type": "object",
+            "title": "TestStructWithSerde",
+            "properties": { "foo": { "type": "number" } },
+            "required": ["foo"],
+            "comment": "Test comment"
+        });
+        assert_eq!(schema, expected);
+        assert!(tests::valid(&TestStructWithSerde {
+            skip: 0,
+            renamed: 10,
+        }));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructSkipLast {
+        name: String,
+        #[serde(skip)]
+        cache: u32,
+    }
+
+    #[test]
+    fn test_struct_skip_last() {
+        let schema = TestStructSkipLast::json_schema();
+        assert_eq!(
+            schema["properties"],
+            json!({ "name": { "type": "string" } })
+        );
+        assert_eq!(schema["required"], json!(["name"]));
+        assert!(tests::valid(&TestStructSkipLast {
+            name: "test".to_string(),
+            cache: 1,
+        }));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[json_schema(comment = "Test comment")]
+    #[allow(dead_code)]
+    struct TestStructWithFlatten {
+        #[serde(flatten)]
+        inner: TestStructWithSerde,
+    }
+
+    #[test]
+    fn test_struct_with_flatten() {
+        let schema = TestStructWithFlatten::json_schema();
+        let expected = json!({
+            "type": "object",
+            "title": "TestStructWithFlatten",
+            "properties": { "foo": { "type": "number" } },
+            "required": ["foo"],
+            "comment": "Test comment"
+        });
+        println!("{:#?}", schema);
+        assert_eq!(schema, expected);
+        assert!(tests::valid(&TestStructWithFlatten {
+            inner: TestStructWithSerde {
+                skip: 0,
+                renamed: 10,
+            }
+        }));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructOnlyFlatten {
+        #[serde(flatten)]
+        inner: TestStructWithFlatten,
+    }
+
+    #[test]
+    fn test_struct_only_flatten() {
+        let schema = TestStructOnlyFlatten::json_schema();
+        let expected = json!({
+            "type": "object",
+            "title": "TestStructOnlyFlatten",
+            "properties": { "foo": { "type": "number" } },
+            "required": ["foo"]
+        });
+        assert_eq!(schema, expected);
+        assert!(tests::valid(&TestStructOnlyFlatten {
+            inner: TestStructWithFlatten {
+                inner: TestStructWithSerde {
+                    skip: 0,
+                    renamed: 10,
+                }
+            }
+        }));
+        assert!(!jsonschema::is_valid(&schema, &json!([])));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructOptional {
+        nickname: Option<String>,
+        age: Option<u32>,
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructWithFlattenOptional {
+        name: String,
+        #[serde(flatten)]
+        optional: TestStructOptional,
+        #[serde(flatten)]
+        maybe: Option<TestStructWithSerde>,
+    }
+
+    #[test]
+    fn test_struct_with_flatten_optional() {
+        let schema = TestStructWithFlattenOptional::json_schema();
+        let expected = json!({
+            "type": "object",
+            "title": "TestStructWithFlattenOptional",
+            "properties": {
+                "name": { "type": "string" },
+                "nickname": { "type": "string" },
+                "age": { "type": "number" },
+                "foo": { "type": "number" }
+            },
+            "required": ["name"]
+        });
+        assert_eq!(schema, expected);
+        assert!(tests::valid(&TestStructWithFlattenOptional {
+            name: "test".to_string(),
+            optional: TestStructOptional {
+                nickname: Some("t".to_string()),
+                age: Some(10),
+            },
+            maybe: Some(TestStructWithSerde {
+                skip: 0,
+                renamed: 10,
+            }),
+        }));
+        assert!(jsonschema::is_valid(&schema, &json!({ "name": "test" })));
+
+        let config = JsonSchemaConfig {
+            nullable: Nullable::AnyOf,
+            ..Default::default()
+        };
+        let schema = TestStructWithFlattenOptional::json_schema_with(&config);
+        assert_eq!(schema["properties"]["foo"], json!({ "type": "number" }));
+        assert_eq!(
+            schema["properties"]["nickname"],
+            json!({ "anyOf": [{ "type": "string" }, { "type": "null" }] })
+        );
+        assert_eq!(schema["required"], json!(["name"]));
+        let instance = TestStructWithFlattenOptional {
+            name: "test".to_string(),
+            optional: TestStructOptional {
+                nickname: None,
+                age: Some(10),
+            },
+            maybe: Some(TestStructWithSerde {
+                skip: 0,
+                renamed: 10,
+            }),
+        };
+        let value = serde_json::to_value(&instance).unwrap();
+        assert!(jsonschema::is_valid(&schema, &value));
+        assert!(!jsonschema::is_valid(
+            &schema,
+            &json!({ "name": "test", "foo": "not a number" })
+        ));
+    }
+
+    #[derive(JsonSchema)]
+    #[json_schema(rename_prefix = "x_")]
+    #[allow(dead_code)]
+    struct TestStructRenamePrefix {
+        name: String,
+        #[serde(rename = "userAge")]
+        age: Option<u32>,
+    }
+
+    #[test]
+    fn test_struct_rename_prefix() {
+        let schema = TestStructRenamePrefix::json_schema();
+        let expected = json!({
+            "type": "object",
+            "title": "TestStructRenamePrefix",
+            "properties": {
+                "x_name": { "type": "string" },
+                "x_userAge": { "type": "number" }
+            },
+            "required": ["x_name"]
+        });
+        assert_eq!(schema, expected);
+        assert!(jsonschema::is_valid(
+            &schema,
+            &json!({ "x_name": "test", "x_userAge": 10 })
+        ));
+        assert!(!jsonschema::is_valid(&schema, &json!({ "x_userAge": 10 })));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructWithFlattenMap {
+        name: String,
+        #[serde(flatten)]
+        extra: HashMap<String, u32>,
+    }
+
+    #[test]
+    fn test_struct_with_flatten_map() {
+        let schema = TestStructWithFlattenMap::json_schema();
+        let expected = json!({
+            "type": "object",
+            "title": "TestStructWithFlattenMap",
+            "properties": { "name": { "type": "string" } },
+            "required": ["name"],
+            "additionalProperties": { "type": "number" }
+        });
+        assert_eq!(schema, expected);
+        assert!(tests::valid(&TestStructWithFlattenMap {
+            name: "test".to_string(),
+            extra: HashMap::from([("a".to_string(), 1), ("b".to_string(), 2)]),
+        }));
+        assert!(!jsonschema::is_valid(
+            &schema,
+            &json!({ "name": "test", "a": "not a number" })
+        ));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructWithFlattenEnum {
+        name: String,
+        #[serde(flatten)]
+        kind: EnumUnitSerdeTag,
+    }
+
+    #[test]
+    fn test_struct_with_flatten_enum() {
+        let schema = TestStructWithFlattenEnum::json_schema();
+        assert_eq!(
+            schema["properties"],
+            json!({ "name": { "type": "string" } })
+        );
+        assert_eq!(schema["required"], json!(["name"]));
+        assert_eq!(
+            schema["allOf"],
+            json!([{
+                "oneOf": [
+                    { "type": "object", "properties": { "type": { "type": "string", "const": "A" } }, "required": ["type"] },
+                    { "type": "object", "properties": { "type": { "type": "string", "const": "B" } }, "required": ["type"] }
+                ]
+            }])
+        );
+        assert!(tests::valid(&TestStructWithFlattenEnum {
+            name: "test".to_string(),
+            kind: EnumUnitSerdeTag::B,
+        }));
+        assert!(!jsonschema::is_valid(
+            &schema,
+            &json!({ "name": "test", "type": "C" })
+        ));
     }
 
     #[derive(JsonSchema, Serialize)]
-    #[json_schema(comment = "Test comment")]
     #[allow(dead_code)]
-    enum EnumUnnamed {
-        A(String),
-        B(u32),
+    #[serde(deny_unknown_fields)]
+    struct TestStructDenyUnknownFieldsFlattenEnum {
+        name: String,
+        #[serde(flatten)]
+        kind: EnumUnitSerdeTag,
     }
 
     #[test]
-    fn test_enum_unit_unnamed() {
-        let schema = EnumUnnamed::json_schema();
-        let expected = json!({
-            "type": "object",
-            "comment": "Test comment",
-            "properties": {
-                "A": { "type": "string" },
-                "B": { "type": "number" },
-            }
-        });
-        assert_eq!(schema, expected);
-        assert!(valid(&EnumUnnamed::A("test".to_string())));
-        assert!(valid(&EnumUnnamed::B(10)));
+    fn test_struct_deny_unknown_fields_flatten_enum() {
+        let instance = TestStructDenyUnknownFieldsFlattenEnum {
+            name: "test".to_string(),
+            kind: EnumUnitSerdeTag::A,
+        };
+        let unknown = json!({ "name": "test", "type": "A", "other": 1 });
+
+        let schema = TestStructDenyUnknownFieldsFlattenEnum::json_schema();
+        assert_eq!(schema["unevaluatedProperties"], json!(false));
+        assert!(tests::valid(&instance));
+        assert!(!jsonschema::is_valid(&schema, &unknown));
+
+        let config = JsonSchemaConfig {
+            draft: Draft::Draft07,
+            include_schema: true,
+            ..Default::default()
+        };
+        let schema = TestStructDenyUnknownFieldsFlattenEnum::json_schema_with(&config);
+        assert_eq!(schema["additionalProperties"], json!(false));
+        assert_eq!(schema["properties"]["type"], json!(true));
+        let value = serde_json::to_value(&instance).unwrap();
+        assert!(jsonschema::is_valid(&schema, &value));
+        assert!(!jsonschema::is_valid(&schema, &unknown));
     }
 
     #[derive(JsonSchema, Serialize)]
-    #[json_schema(comment = "Test comment")]
     #[allow(dead_code)]
-    enum EnumNamed {
-        A { name: String },
-        B { age: u32 },
+    #[serde(deny_unknown_fields)]
+    struct TestStructDenyUnknownFields {
+        name: String,
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    #[serde(deny_unknown_fields)]
+    struct TestStructDenyUnknownFieldsFlatten {
+        name: String,
+        #[serde(flatten)]
+        inner: TestStructWithSerde,
     }
 
     #[test]
-    fn test_enum_named() {
-        let schema = EnumNamed::json_schema();
-        let expected = json!({
-            "type": "object",
-            "comment": "Test comment",
-            "properties": {
-                "A": { "type": "object", "properties": { "name": { "type": "string" } }, "required": ["name"] },
-                "B": { "type": "object", "properties": { "age": { "type": "number" } }, "required": ["age"] },
-            }
-        });
-        assert_eq!(schema, expected);
-        assert!(valid(&EnumNamed::A {
+    fn test_struct_deny_unknown_fields() {
+        let schema = TestStructDenyUnknownFields::json_schema();
+        assert_eq!(schema["additionalProperties"], json!(false));
+        assert!(tests::valid(&TestStructDenyUnknownFields {
             name: "test".to_string()
         }));
-        assert!(valid(&EnumNamed::B { age: 10 }));
+        assert!(!jsonschema::is_valid(
+            &schema,
+            &json!({ "name": "test", "other": 1 })
+        ));
+
+        let schema = TestStructDenyUnknownFieldsFlatten::json_schema();
+        assert_eq!(schema["unevaluatedProperties"], json!(false));
+        assert!(schema.get("additionalProperties").is_none());
+        assert!(tests::valid(&TestStructDenyUnknownFieldsFlatten {
+            name: "test".to_string(),
+            inner: TestStructWithSerde {
+                skip: 0,
+                renamed: 10,
+            },
+        }));
+        assert!(!jsonschema::is_valid(
+            &schema,
+            &json!({ "name": "test", "foo": 10, "other": 1 })
+        ));
+
+        let config = JsonSchemaConfig {
+            draft: Draft::Draft07,
+            ..Default::default()
+        };
+        let schema = TestStructDenyUnknownFieldsFlatten::json_schema_with(&config);
+        assert_eq!(schema["additionalProperties"], json!(false));
     }
 
     #[derive(JsonSchema, Serialize)]
     #[allow(dead_code)]
-    /// Test description
-    struct TestStructDoc {
-        /// Test field description
-        name: String,
+    #[serde(tag = "kind")]
+    enum EnumSerdeTagRenamedVariant {
+        #[serde(rename = "sign_in")]
+        Login {
+            user: String,
+        },
+        Logout,
     }
 
     #[test]
-    fn test_struct_doc() {
-        let schema = TestStructDoc::json_schema();
-        let expected = json!({ "type": "object", "description": "Test description", "properties": { "name": { "type": "string", "description": "Test field description" } }, "required": ["name"] });
-        assert_eq!(schema, expected);
-        assert!(valid(&TestStructDoc {
-            name: "test".to_string()
+    fn test_enum_serde_tag_renamed_variant() {
+        let schema = EnumSerdeTagRenamedVariant::json_schema();
+        assert_eq!(
+            schema["oneOf"][0]["properties"]["kind"],
+            json!({ "type": "string", "const": "sign_in" })
+        );
+        assert_eq!(
+            schema["oneOf"][1]["properties"]["kind"],
+            json!({ "type": "string", "const": "Logout" })
+        );
+        assert!(tests::valid(&EnumSerdeTagRenamedVariant::Login {
+            user: "test".to_string()
         }));
+        assert!(tests::valid(&EnumSerdeTagRenamedVariant::Logout));
+        assert!(!jsonschema::is_valid(
+            &schema,
+            &json!({ "kind": "Login", "user": "test" })
+        ));
     }
-}
 
-#[cfg(feature = "serde-compat")]
-#[cfg(test)]
-mod tests_serde_compat {
-    use super::*;
-    use serde::Serialize;
-    use serde_json::json;
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    enum EnumRenamedVariant {
+        #[serde(rename = "sign_in")]
+        Login { user: String },
+        #[serde(rename = "sign_out")]
+        Logout,
+    }
 
     #[derive(JsonSchema, Serialize)]
-    #[json_schema(comment = "Test comment")]
     #[allow(dead_code)]
-    struct TestStructWithSerde {
-        #[serde(skip)]
-        skip: u32,
-        #[serde(rename = "foo")]
-        renamed: u32,
+    enum EnumUnitRenamedVariant {
+        #[serde(rename = "on")]
+        Enabled,
+        Disabled,
     }
 
     #[test]
-    fn test_struct_with_serde() {
-        let schema = TestStructWithSerde::json_schema();
-        let expected = json!({
-            "type": "object",
-            "properties": { "foo": { "type": "number" } },
-            "required": ["foo"],
-            "comment": "Test comment"
-        });
-        assert_eq!(schema, expected);
-        assert!(tests::valid(&TestStructWithSerde {
-            skip: 0,
-            renamed: 10,
+    fn test_enum_renamed_variant() {
+        let schema = EnumRenamedVariant::json_schema();
+        assert_eq!(schema["oneOf"][0]["required"], json!(["sign_in"]));
+        assert_eq!(schema["oneOf"][1]["const"], json!("sign_out"));
+        assert!(tests::valid(&EnumRenamedVariant::Login {
+            user: "test".to_string()
+        }));
+        assert!(tests::valid(&EnumRenamedVariant::Logout));
+        assert!(!jsonschema::is_valid(&schema, &json!("Logout")));
+
+        let schema = EnumUnitRenamedVariant::json_schema();
+        assert_eq!(schema["enum"], json!(["on", "Disabled"]));
+        assert!(tests::valid(&EnumUnitRenamedVariant::Enabled));
+        assert!(tests::valid(&EnumUnitRenamedVariant::Disabled));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code, non_snake_case)]
+    #[serde(rename_all = "lowercase")]
+    struct TestStructRenameAllLowercase {
+        UserName: String,
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code, non_snake_case)]
+    #[serde(rename_all = "UPPERCASE")]
+    struct TestStructRenameAllUppercase {
+        UserName: String,
+    }
+
+    #[test]
+    fn test_struct_rename_all_case_folding() {
+        let schema = TestStructRenameAllLowercase::json_schema();
+        assert_eq!(
+            schema["properties"],
+            json!({ "UserName": { "type": "string" } })
+        );
+        assert_eq!(schema["required"], json!(["UserName"]));
+        assert!(tests::valid(&TestStructRenameAllLowercase {
+            UserName: "test".to_string()
+        }));
+        let schema = TestStructRenameAllUppercase::json_schema();
+        assert_eq!(
+            schema["properties"],
+            json!({ "USERNAME": { "type": "string" } })
+        );
+        assert!(tests::valid(&TestStructRenameAllUppercase {
+            UserName: "test".to_string()
         }));
     }
 
     #[derive(JsonSchema, Serialize)]
-    #[json_schema(comment = "Test comment")]
     #[allow(dead_code)]
-    struct TestStructWithFlatten {
-        #[serde(flatten)]
-        inner: TestStructWithSerde,
+    #[serde(tag = "type", rename_all_fields = "camelCase")]
+    enum EnumRenameAllFields {
+        Login {
+            user_name: String,
+            remember_me: bool,
+        },
+        #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+        Logout { session_id: u32 },
     }
 
     #[test]
-    fn test_struct_with_flatten() {
-        let schema = TestStructWithFlatten::json_schema();
-        let expected = json!({
-            "type": "object",
-            "properties": { "foo": { "type": "number" } },
-            "required": ["foo"],
-            "comment": "Test comment"
-        });
-        println!("{:#?}", schema);
-        assert_eq!(schema, expected);
-        assert!(tests::valid(&TestStructWithFlatten {
-            inner: TestStructWithSerde {
-                skip: 0,
-                renamed: 10,
-            }
+    fn test_enum_rename_all_fields() {
+        let schema = EnumRenameAllFields::json_schema();
+        assert_eq!(
+            schema["oneOf"][0]["properties"],
+            json!({
+                "userName": { "type": "string" },
+                "rememberMe": { "type": "boolean" },
+                "type": { "type": "string", "const": "Login" }
+            })
+        );
+        assert_eq!(
+            schema["oneOf"][1]["properties"],
+            json!({
+                "SESSION_ID": { "type": "number" },
+                "type": { "type": "string", "const": "Logout" }
+            })
+        );
+        assert!(tests::valid(&EnumRenameAllFields::Login {
+            user_name: "test".to_string(),
+            remember_me: true,
         }));
+        assert!(tests::valid(&EnumRenameAllFields::Logout { session_id: 1 }));
+        assert!(!jsonschema::is_valid(
+            &schema,
+            &json!({ "type": "Logout", "session_id": 1 })
+        ));
     }
 
     #[derive(JsonSchema, Serialize)]
@@ -433,8 +3941,8 @@ mod tests_serde_compat {
         let schema = EnumUnitSerdeTag::json_schema();
         let expected = json!({
             "oneOf": [
-                { "type": "object", "properties": { "type": { "type": "string", "const": "A" } }, "required": ["type"] },
-                { "type": "object", "properties": { "type": { "type": "string", "const": "B" } }, "required": ["type"] }
+                { "type": "object", "properties": { "type": { "type": "string", "const": "A" } }, "required": ["type"], "additionalProperties": false },
+                { "type": "object", "properties": { "type": { "type": "string", "const": "B" } }, "required": ["type"], "additionalProperties": false }
             ]
         });
         assert_eq!(schema, expected);
@@ -451,14 +3959,311 @@ mod tests_serde_compat {
         C,
     }
 
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    #[serde(tag = "type")]
+    #[json_schema(hoist_required)]
+    enum EnumHoistSerdeTag {
+        A { id: u32, name: String },
+        B { id: u32 },
+    }
+
+    #[test]
+    fn test_enum_hoist_required() {
+        let schema = EnumHoistSerdeTag::json_schema();
+        let expected = json!({
+            "required": ["id", "type"],
+            "oneOf": [
+                { "type": "object", "properties": { "type": { "type": "string", "const": "A" }, "id": { "type": "number" }, "name": { "type": "string" } }, "required": ["name"], "additionalProperties": false },
+                { "type": "object", "properties": { "type": { "type": "string", "const": "B" }, "id": { "type": "number" } }, "required": [], "additionalProperties": false }
+            ]
+        });
+        assert_eq!(schema, expected);
+        assert!(tests::valid(&EnumHoistSerdeTag::A {
+            id: 1,
+            name: "test".to_string()
+        }));
+        assert!(tests::valid(&EnumHoistSerdeTag::B { id: 1 }));
+        assert!(!jsonschema::is_valid(&schema, &json!({ "type": "B" })));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct EventMetadata {
+        id: u32,
+        source: Option<String>,
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    #[serde(tag = "type")]
+    enum EnumSerdeTagFlatten {
+        Created {
+            name: String,
+            #[serde(flatten)]
+            metadata: EventMetadata,
+        },
+        Deleted {
+            #[serde(flatten)]
+            metadata: EventMetadata,
+        },
+    }
+
+    #[test]
+    fn test_enum_serde_tag_flatten() {
+        let schema = EnumSerdeTagFlatten::json_schema();
+        let expected = json!({
+            "oneOf": [
+                { "type": "object", "properties": { "name": { "type": "string" }, "id": { "type": "number" }, "source": { "type": "string" }, "type": { "type": "string", "const": "Created" } }, "required": ["name", "id", "type"] },
+                { "type": "object", "properties": { "id": { "type": "number" }, "source": { "type": "string" }, "type": { "type": "string", "const": "Deleted" } }, "required": ["id", "type"] }
+            ]
+        });
+        assert_eq!(schema, expected);
+        assert!(tests::valid(&EnumSerdeTagFlatten::Created {
+            name: "test".to_string(),
+            metadata: EventMetadata {
+                id: 1,
+                source: Some("test".to_string()),
+            },
+        }));
+        assert!(tests::valid(&EnumSerdeTagFlatten::Deleted {
+            metadata: EventMetadata {
+                id: 2,
+                source: Some("test".to_string()),
+            },
+        }));
+        assert!(!jsonschema::is_valid(
+            &schema,
+            &json!({ "type": "Deleted" })
+        ));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    #[serde(tag = "type")]
+    enum EnumSerdeTagDocumented {
+        /// A user signed in
+        Login { user: String },
+        #[json_schema(title = "Sign out")]
+        Logout,
+    }
+
+    #[test]
+    fn test_enum_serde_tag_variant_description() {
+        let schema = EnumSerdeTagDocumented::json_schema();
+        assert_eq!(schema["oneOf"][0]["description"], json!("A user signed in"));
+        assert_eq!(schema["oneOf"][1]["title"], json!("Sign out"));
+        assert!(tests::valid(&EnumSerdeTagDocumented::Login {
+            user: "test".to_string()
+        }));
+        assert!(tests::valid(&EnumSerdeTagDocumented::Logout));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    #[serde(tag = "type")]
+    #[json_schema(allow_unknown_fields)]
+    enum EnumSerdeTagOpen {
+        A { name: String },
+    }
+
+    #[test]
+    fn test_enum_serde_tag_allow_unknown_fields() {
+        let schema = EnumSerdeTagOpen::json_schema();
+        assert!(schema["oneOf"][0].get("additionalProperties").is_none());
+        assert!(jsonschema::is_valid(
+            &schema,
+            &json!({ "type": "A", "name": "test", "extra": 1 })
+        ));
+    }
+
+    mod as_string {
+        pub fn serialize<S: serde::Serializer>(
+            value: &u32,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(value)
+        }
+    }
+
+    fn string_schema() -> serde_json::Value {
+        json!({ "type": "string", "pattern": "^[0-9]+$" })
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructSerdeWithOverride {
+        #[serde(with = "as_string")]
+        #[json_schema(with = "string_schema")]
+        id: u32,
+    }
+
+    // Without an override the derive warns that the schema accepts any value.
+    #[test]
+    #[allow(deprecated)]
+    fn test_struct_serde_with() {
+        #[derive(JsonSchema, Serialize)]
+        #[allow(dead_code)]
+        struct TestStructSerdeWith {
+            #[serde(with = "as_string")]
+            #[json_schema(description = "Numeric id")]
+            id: u32,
+        }
+
+        let schema = TestStructSerdeWith::json_schema();
+        assert_eq!(
+            schema["properties"]["id"],
+            json!({ "description": "Numeric id" })
+        );
+        assert!(tests::valid(&TestStructSerdeWith { id: 1 }));
+
+        let schema = TestStructSerdeWithOverride::json_schema();
+        assert_eq!(schema["properties"]["id"], string_schema());
+        assert!(tests::valid(&TestStructSerdeWithOverride { id: 1 }));
+    }
+
+    mod as_base64 {
+        const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+        pub fn serialize<S: serde::Serializer>(
+            bytes: &[u8],
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            let mut encoded = String::new();
+            for chunk in bytes.chunks(3) {
+                let n = chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0u32, |n, (i, byte)| n | (*byte as u32) << (16 - 8 * i));
+                for i in 0..4 {
+                    match i <= chunk.len() {
+                        true => encoded.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char),
+                        false => encoded.push('='),
+                    }
+                }
+            }
+            serializer.serialize_str(&encoded)
+        }
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructSerdeWithBytes {
+        #[serde(with = "as_base64")]
+        #[json_schema(bytes = "base64")]
+        data: Vec<u8>,
+    }
+
+    #[test]
+    fn test_struct_serde_with_bytes() {
+        let schema = TestStructSerdeWithBytes::json_schema();
+        assert_eq!(
+            schema["properties"]["data"],
+            json!({ "type": "string", "contentEncoding": "base64" })
+        );
+        let instance = TestStructSerdeWithBytes {
+            data: b"test".to_vec(),
+        };
+        assert_eq!(
+            serde_json::to_value(&instance).unwrap()["data"],
+            json!("dGVzdA==")
+        );
+        assert!(tests::valid(&instance));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct TestStructAlias {
+        #[serde(alias = "userName", alias = "login")]
+        user_name: String,
+        age: u32,
+    }
+
+    #[test]
+    fn test_struct_alias() {
+        let schema = TestStructAlias::json_schema();
+        assert_eq!(
+            schema["properties"]["user_name"],
+            json!({ "type": "string", "x-aliases": ["userName", "login"] })
+        );
+        assert!(schema["properties"]["age"].get("x-aliases").is_none());
+        assert!(tests::valid(&TestStructAlias {
+            user_name: "test".to_string(),
+            age: 10,
+        }));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    #[serde(tag = "type")]
+    enum EnumSerdeTagOptional {
+        Login {
+            user: String,
+            remember: Option<bool>,
+        },
+    }
+
+    #[test]
+    fn test_enum_serde_tag_optional_field() {
+        let schema = EnumSerdeTagOptional::json_schema();
+        assert_eq!(schema["oneOf"][0]["required"], json!(["user", "type"]));
+        assert_eq!(
+            schema["oneOf"][0]["properties"]["remember"],
+            json!({ "type": "boolean" })
+        );
+        assert!(tests::valid(&EnumSerdeTagOptional::Login {
+            user: "test".to_string(),
+            remember: Some(true),
+        }));
+        assert!(jsonschema::is_valid(
+            &schema,
+            &json!({ "type": "Login", "user": "test" })
+        ));
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    struct EventUser {
+        name: String,
+    }
+
+    #[derive(JsonSchema, Serialize)]
+    #[allow(dead_code)]
+    #[serde(tag = "type")]
+    enum EnumSerdeTagRef {
+        Login { user: EventUser },
+        Logout,
+    }
+
+    #[test]
+    fn test_enum_serde_tag_defs() {
+        let config = JsonSchemaConfig {
+            inline: false,
+            ..Default::default()
+        };
+        let schema = EnumSerdeTagRef::json_schema_with(&config);
+        assert_eq!(
+            schema["oneOf"][0]["properties"]["user"],
+            json!({ "$ref": "#/$defs/EventUser" })
+        );
+        assert_eq!(schema["$defs"]["EventUser"], EventUser::json_schema());
+        let instance = serde_json::to_value(EnumSerdeTagRef::Login {
+            user: EventUser {
+                name: "test".to_string(),
+            },
+        })
+        .unwrap();
+        assert!(jsonschema::is_valid(&schema, &instance));
+    }
+
     #[test]
     fn test_enum_named_serde_tag() {
         let schema = EnumNamedSerdeTag::json_schema();
         let expected = json!({
             "oneOf": [
-                { "type": "object", "properties": { "type": { "type": "string", "const": "A" }, "name": { "type": "string" } }, "required": ["name", "type"] },
-                { "type": "object", "properties": { "type": { "type": "string", "const": "B" }, "age": { "type": "number" } }, "required": ["age", "type"] },
-                { "type": "object", "properties": { "type": { "type": "string", "const": "C" } }, "required": ["type"] }
+                { "type": "object", "properties": { "type": { "type": "string", "const": "A" }, "name": { "type": "string" } }, "required": ["name", "type"], "additionalProperties": false },
+                { "type": "object", "properties": { "type": { "type": "string", "const": "B" }, "age": { "type": "number" } }, "required": ["age", "type"], "additionalProperties": false },
+                { "type": "object", "properties": { "type": { "type": "string", "const": "C" } }, "required": ["type"], "additionalProperties": false }
             ]
         });
         assert_eq!(schema, expected);
@@ -466,5 +4271,9 @@ mod tests_serde_compat {
             name: "test".to_string()
         }));
         assert!(tests::valid(&EnumNamedSerdeTag::B { age: 10 }));
+        assert!(!jsonschema::is_valid(
+            &schema,
+            &json!({ "type": "B", "age": 10, "name": "test" })
+        ));
     }
 }
@@ -0,0 +1,105 @@
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use serde_json::{Map, Value};
+
+/// Typed view of a generated JSON Schema.
+///
+/// The common structural keywords have their own fields; every other keyword is kept
+/// in `extra`. Converting to and from [`serde_json::Value`] is lossless for object
+/// schemas.
+///
+/// ```rust
+/// use json_schema_derive::{JsonSchema, Schema};
+///
+/// let schema: Schema = User::schema();
+/// assert_eq!(schema.ty, Some("object".into()));
+/// let value: serde_json::Value = schema.into();
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Schema {
+    /// The `type` keyword, a type name or an array of type names.
+    pub ty: Option<Value>,
+    /// The `properties` keyword, mapping property names to their schemas.
+    pub properties: Option<Map<String, Value>>,
+    /// The `required` keyword.
+    pub required: Option<Vec<String>>,
+    /// The `items` keyword.
+    pub items: Option<Value>,
+    /// Every other keyword of the schema.
+    pub extra: Map<String, Value>,
+}
+
+impl From<Value> for Schema {
+    /// Split an object schema into its typed fields.
+    ///
+    /// The boolean schema `false` becomes `{ "not": {} }`; any other non-object value
+    /// accepts everything and becomes the empty schema.
+    fn from(value: Value) -> Self {
+        let mut map = match value {
+            Value::Object(map) => map,
+            Value::Bool(false) => {
+                let mut extra = Map::new();
+                extra.insert("not".into(), Value::Object(Map::new()));
+                return Self {
+                    extra,
+                    ..Default::default()
+                };
+            }
+            _ => return Self::default(),
+        };
+        // Keywords whose value does not fit the typed field stay in `extra`.
+        let properties = match map.remove("properties") {
+            Some(Value::Object(properties)) => Some(properties),
+            Some(other) => {
+                map.insert("properties".into(), other);
+                None
+            }
+            None => None,
+        };
+        let required = match map.remove("required") {
+            Some(Value::Array(required)) if required.iter().all(Value::is_string) => Some(
+                required
+                    .into_iter()
+                    .filter_map(|key| key.as_str().map(str::to_owned))
+                    .collect(),
+            ),
+            Some(other) => {
+                map.insert("required".into(), other);
+                None
+            }
+            None => None,
+        };
+        Self {
+            ty: map.remove("type"),
+            properties,
+            required,
+            items: map.remove("items"),
+            extra: map,
+        }
+    }
+}
+
+impl From<Schema> for Value {
+    fn from(schema: Schema) -> Self {
+        let mut map = Map::new();
+        if let Some(ty) = schema.ty {
+            map.insert("type".into(), ty);
+        }
+        if let Some(properties) = schema.properties {
+            map.insert("properties".into(), Value::Object(properties));
+        }
+        if let Some(required) = schema.required {
+            map.insert(
+                "required".into(),
+                Value::Array(required.into_iter().map(Value::String).collect()),
+            );
+        }
+        if let Some(items) = schema.items {
+            map.insert("items".into(), items);
+        }
+        map.extend(schema.extra);
+        Value::Object(map)
+    }
+}
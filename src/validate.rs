@@ -0,0 +1,41 @@
+#[cfg(feature = "validate")]
+use std::sync::OnceLock;
+
+/// Storage for the compiled validator of a type, generated by the derive macro so that
+/// repeated calls to `JsonSchema::validate` compile the schema only once.
+#[doc(hidden)]
+#[derive(Default)]
+pub struct ValidatorCell {
+    #[cfg(feature = "validate")]
+    validator: OnceLock<jsonschema::Validator>,
+}
+
+impl ValidatorCell {
+    pub const fn new() -> Self {
+        Self {
+            #[cfg(feature = "validate")]
+            validator: OnceLock::new(),
+        }
+    }
+}
+
+#[cfg(feature = "validate")]
+impl ValidatorCell {
+    /// The compiled validator, if a schema has been compiled yet.
+    pub fn get(&self) -> Option<&jsonschema::Validator> {
+        self.validator.get()
+    }
+
+    /// The compiled validator, compiling it with `compile` on first use.
+    pub fn get_or_try_init<E>(
+        &self,
+        compile: impl FnOnce() -> Result<jsonschema::Validator, E>,
+    ) -> Result<&jsonschema::Validator, E> {
+        if let Some(validator) = self.validator.get() {
+            return Ok(validator);
+        }
+        let validator = compile()?;
+        // Another thread may have won the race; either validator is equivalent.
+        Ok(self.validator.get_or_init(|| validator))
+    }
+}
@@ -0,0 +1,123 @@
+//! Derives `JsonSchema` in a `no_std` crate. Build it on its own, so that neither
+//! `json-schema-derive` nor `serde_json` has `std` enabled:
+//!
+//! ```sh
+//! cargo build -p json-schema-derive-no-std
+//! ```
+#![no_std]
+#![allow(dead_code)]
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use json_schema_derive::JsonSchema;
+
+/// A user
+/// of the service.
+#[derive(JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[json_schema(allOf = ["Base"], minProperties = 1, dependentRequired(age = ["name"]))]
+struct User {
+    #[json_schema(minLength = 3, example = "alice")]
+    name: String,
+    #[json_schema(integer, examples = [1, 2])]
+    age: Option<u32>,
+    tags: Vec<String>,
+    #[json_schema(key_min_length = 1)]
+    scores: BTreeMap<String, f64>,
+    parent: Option<Box<User>>,
+    #[json_schema(schema = r#"{ "type": "string", "format": "uri" }"#)]
+    homepage: String,
+    #[json_schema("x-internal" = { "owner": "accounts" })]
+    note: Option<String>,
+    #[serde(flatten)]
+    base: Base,
+    #[serde(flatten)]
+    kind: Kind,
+}
+
+#[derive(JsonSchema)]
+struct Base {
+    id: u64,
+}
+
+#[derive(JsonSchema)]
+#[serde(tag = "type")]
+#[json_schema(hoist_required)]
+enum Kind {
+    Admin { level: u8 },
+    Guest { level: u8 },
+}
+
+#[derive(JsonSchema)]
+struct Pair(String, u32);
+
+#[derive(JsonSchema)]
+struct Id(u64);
+
+#[derive(JsonSchema)]
+struct Marker;
+
+#[derive(JsonSchema)]
+#[json_schema(variant_examples)]
+enum Status {
+    /// Waiting to start
+    Pending,
+    Done,
+}
+
+#[derive(JsonSchema)]
+#[json_schema(repr = "int")]
+enum Code {
+    Ok = 200,
+    NotFound = 404,
+}
+
+#[derive(JsonSchema)]
+#[json_schema(as = "array_of_variants")]
+enum Permission {
+    Read,
+    Write,
+}
+
+#[derive(JsonSchema)]
+#[json_schema(variant_examples)]
+enum Shape {
+    Circle(f64),
+    Square(u32),
+    Empty,
+}
+
+#[derive(JsonSchema)]
+enum Event {
+    Login { user: String },
+    Moved(i32, i32),
+    Logout,
+}
+
+#[derive(JsonSchema)]
+#[json_schema(draft = "07", source_comment)]
+struct Wrapper<T> {
+    value: T,
+    values: Vec<T>,
+}
+
+pub fn schemas() -> Vec<serde_json::Value> {
+    alloc::vec![
+        User::json_schema(),
+        json_schema_derive::bundle::<User>(),
+        Pair::json_schema(),
+        Id::json_schema(),
+        Marker::json_schema(),
+        Status::json_schema(),
+        Code::json_schema(),
+        Permission::json_schema(),
+        Shape::json_schema(),
+        Event::json_schema(),
+        Wrapper::<Code>::json_schema(),
+    ]
+}
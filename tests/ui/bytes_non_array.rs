@@ -0,0 +1,9 @@
+use json_schema_derive::JsonSchema;
+
+#[derive(JsonSchema)]
+struct Upload {
+    #[json_schema(bytes = "base64")]
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use json_schema_derive::JsonSchema;
+
+#[derive(JsonSchema)]
+#[json_schema(schema = "http://json-schema.org/draft-07/schema#")]
+struct User {
+    name: String,
+}
+
+fn main() {}
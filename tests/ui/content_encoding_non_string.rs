@@ -0,0 +1,9 @@
+use json_schema_derive::JsonSchema;
+
+#[derive(JsonSchema)]
+struct Upload {
+    #[json_schema(contentEncoding = "base64")]
+    size: u64,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use json_schema_derive::JsonSchema;
+
+#[derive(JsonSchema)]
+struct User {
+    #[json_schema(minLength = 2)]
+    #[json_schema(minLength = 3)]
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use json_schema_derive::JsonSchema;
+
+#[derive(JsonSchema)]
+enum Status {
+    #[json_schema(description = "Ready to go")]
+    #[json_schema(description = "Waiting")]
+    Ready,
+    Done,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use json_schema_derive::JsonSchema;
+
+#[derive(JsonSchema)]
+enum Shape {
+    Circle {
+        #[json_schema(minimum = 0)]
+        #[json_schema(minimum = 1)]
+        radius: f64,
+    },
+}
+
+fn main() {}
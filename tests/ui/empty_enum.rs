@@ -0,0 +1,6 @@
+use json_schema_derive::JsonSchema;
+
+#[derive(JsonSchema)]
+enum Empty {}
+
+fn main() {}
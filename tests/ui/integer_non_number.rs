@@ -0,0 +1,9 @@
+use json_schema_derive::JsonSchema;
+
+#[derive(JsonSchema)]
+struct User {
+    #[json_schema(integer)]
+    name: String,
+}
+
+fn main() {}
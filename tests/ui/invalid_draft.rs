@@ -0,0 +1,9 @@
+use json_schema_derive::JsonSchema;
+
+#[derive(JsonSchema)]
+#[json_schema(draft = "04")]
+struct User {
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use json_schema_derive::JsonSchema;
+
+#[derive(JsonSchema)]
+struct User {
+    #[json_schema(with_schema = r#"{ "type": "string", }"#)]
+    homepage: String,
+}
+
+fn main() {}
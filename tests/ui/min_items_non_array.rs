@@ -0,0 +1,9 @@
+use json_schema_derive::JsonSchema;
+
+#[derive(JsonSchema)]
+struct User {
+    #[json_schema(minItems = 1)]
+    name: String,
+}
+
+fn main() {}
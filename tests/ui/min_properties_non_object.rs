@@ -0,0 +1,9 @@
+use json_schema_derive::JsonSchema;
+
+#[derive(JsonSchema)]
+struct User {
+    #[json_schema(minProperties = 1)]
+    tags: Vec<String>,
+}
+
+fn main() {}
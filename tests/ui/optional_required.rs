@@ -0,0 +1,9 @@
+use json_schema_derive::JsonSchema;
+
+#[derive(JsonSchema)]
+struct User {
+    #[json_schema(optional, required)]
+    name: Option<String>,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use json_schema_derive::JsonSchema;
+
+#[derive(JsonSchema)]
+struct User {
+    #[json_schema(readOnly, writeOnly)]
+    id: u32,
+}
+
+fn main() {}